@@ -0,0 +1,34 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use rapla_ical_proxy::calendar::Calendar;
+use rapla_ical_proxy::parser::{parse_calendar, parse_calendar_pages};
+use scraper::Html;
+
+// 26 weeks across a year boundary with a little over 400 events.
+const FIXTURE: &str = include_str!("fixtures/calendar.html");
+
+fn bench_parse(c: &mut Criterion) {
+    let calendar = parse_calendar(FIXTURE, 2023).unwrap();
+    assert!(calendar.events.len() >= 200);
+
+    c.bench_function("parse cold", |b| {
+        b.iter(|| parse_calendar(black_box(FIXTURE), 2023).unwrap())
+    });
+
+    let html = Html::parse_document(FIXTURE);
+    c.bench_function("parse warm", |b| {
+        b.iter(|| Calendar::try_from(black_box(&html)).unwrap())
+    });
+
+    // A year shown as several pages, which is what runs on the thread pool with `parallel`.
+    let pages = [(FIXTURE, 2023); 4];
+    c.bench_function("parse pages", |b| {
+        b.iter(|| parse_calendar_pages(black_box(pages)).unwrap())
+    });
+
+    c.bench_function("to_ics", |b| {
+        b.iter(|| black_box(&calendar).to_ics_string())
+    });
+}
+
+criterion_group!(benches, bench_parse);
+criterion_main!(benches);
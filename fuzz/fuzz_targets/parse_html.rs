@@ -0,0 +1,13 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use rapla_ical_proxy::calendar::Calendar;
+use rapla_ical_proxy::parser::parse_calendar_lossy;
+
+// Parsing may fail on arbitrary input, but it must never panic.
+fuzz_target!(|data: &[u8]| {
+    if let Ok(s) = std::str::from_utf8(data) {
+        let _ = parse_calendar_lossy(s, 2023);
+    }
+    let _ = Calendar::try_from(data);
+});
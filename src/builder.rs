@@ -0,0 +1,229 @@
+use std::fmt;
+
+use chrono::{NaiveDate, NaiveTime};
+
+use crate::calendar::{Calendar, Event};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BuildError {
+    EmptyName,
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EmptyName => write!(f, "calendar name must not be empty"),
+        }
+    }
+}
+
+impl std::error::Error for BuildError {}
+
+/// Builds an [`Event`] without having to spell out every optional field.
+#[derive(Debug, Clone)]
+pub struct EventBuilder {
+    event: Event,
+}
+
+impl EventBuilder {
+    /// Like the parser, an end time before the start time is taken to be on the next day.
+    /// There is no next day after the last representable day, such events end at its last moment instead.
+    pub fn new(
+        date: NaiveDate,
+        start: NaiveTime,
+        end: NaiveTime,
+        title: impl Into<String>,
+    ) -> Self {
+        let (end, end_date) = match date.succ_opt() {
+            Some(next_day) if end < start => (end, next_day),
+            None if end < start => (
+                NaiveTime::from_hms_nano_opt(23, 59, 59, 999_999_999).unwrap(),
+                date,
+            ),
+            _ => (end, date),
+        };
+
+        Self {
+            event: Event {
+                date,
+                start,
+                end,
+                end_date,
+                title: title.into(),
+                locations: Vec::new(),
+                organizer: None,
+                description: None,
+                notes: None,
+                category: None,
+                rapla_url: None,
+                sequence: 0,
+            },
+        }
+    }
+
+    /// Adds a location, can be called multiple times.
+    #[must_use]
+    pub fn location(mut self, location: impl Into<String>) -> Self {
+        self.event.locations.push(location.into());
+        self
+    }
+
+    #[must_use]
+    pub fn organizer(mut self, organizer: impl Into<String>) -> Self {
+        self.event.organizer = Some(organizer.into());
+        self
+    }
+
+    #[must_use]
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.event.description = Some(description.into());
+        self
+    }
+
+    #[must_use]
+    pub fn notes(mut self, notes: impl Into<String>) -> Self {
+        self.event.notes = Some(notes.into());
+        self
+    }
+
+    #[must_use]
+    pub fn category(mut self, category: impl Into<String>) -> Self {
+        self.event.category = Some(category.into());
+        self
+    }
+
+    #[must_use]
+    pub fn rapla_url(mut self, rapla_url: impl Into<String>) -> Self {
+        self.event.rapla_url = Some(rapla_url.into());
+        self
+    }
+
+    #[must_use]
+    pub fn sequence(mut self, sequence: u32) -> Self {
+        self.event.sequence = sequence;
+        self
+    }
+
+    #[must_use]
+    pub fn build(self) -> Event {
+        self.event
+    }
+}
+
+/// Builds a [`Calendar`], unlike the HTML parser this makes sure the name isn't blank.
+#[derive(Debug, Clone)]
+pub struct CalendarBuilder {
+    name: String,
+    events: Vec<Event>,
+}
+
+impl CalendarBuilder {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            events: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn add_event(mut self, event: Event) -> Self {
+        self.events.push(event);
+        self
+    }
+
+    #[must_use]
+    pub fn extend_events(mut self, events: impl IntoIterator<Item = Event>) -> Self {
+        self.events.extend(events);
+        self
+    }
+
+    pub fn build(self) -> Result<Calendar, BuildError> {
+        if self.name.trim().is_empty() {
+            return Err(BuildError::EmptyName);
+        }
+
+        Ok(Calendar {
+            name: self.name,
+            events: self.events,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, NaiveDate, NaiveTime};
+
+    use super::{BuildError, CalendarBuilder, EventBuilder};
+    use crate::validate::ValidationIssue;
+
+    #[test]
+    fn test_event_builder() {
+        let date = NaiveDate::from_ymd_opt(2023, 10, 23).unwrap();
+        let event = EventBuilder::new(
+            date,
+            NaiveTime::from_hms_opt(22, 0, 0).unwrap(),
+            NaiveTime::from_hms_opt(1, 0, 0).unwrap(),
+            "Labor",
+        )
+        .location("A 1.01")
+        .location("B 2.02")
+        .category("category_02")
+        .build();
+
+        assert_eq!(event.title, "Labor");
+        assert_eq!(event.locations, ["A 1.01", "B 2.02"]);
+        assert_eq!(event.category.as_deref(), Some("category_02"));
+        assert_eq!(event.description, None);
+        assert_eq!(event.end_date, date.succ_opt().unwrap());
+    }
+
+    #[test]
+    fn test_event_builder_last_day() {
+        let event = EventBuilder::new(
+            NaiveDate::MAX,
+            NaiveTime::from_hms_opt(22, 0, 0).unwrap(),
+            NaiveTime::from_hms_opt(1, 0, 0).unwrap(),
+            "Labor",
+        )
+        .build();
+        assert_eq!(event.end_date, NaiveDate::MAX);
+        assert!(event.end > event.start);
+        assert!(event.duration() >= Duration::zero());
+
+        let calendar = CalendarBuilder::new("TINF22B")
+            .add_event(event)
+            .build()
+            .unwrap();
+        assert!(!calendar
+            .validate()
+            .iter()
+            .any(|issue| matches!(issue, ValidationIssue::EndBeforeStart { .. })));
+    }
+
+    #[test]
+    fn test_calendar_builder() {
+        let date = NaiveDate::from_ymd_opt(2023, 10, 23).unwrap();
+        let event = |hour| {
+            EventBuilder::new(
+                date,
+                NaiveTime::from_hms_opt(hour, 0, 0).unwrap(),
+                NaiveTime::from_hms_opt(hour + 1, 0, 0).unwrap(),
+                "A",
+            )
+            .build()
+        };
+
+        let calendar = CalendarBuilder::new("TINF22B")
+            .add_event(event(8))
+            .extend_events([event(10), event(12)])
+            .build()
+            .unwrap();
+        assert_eq!(calendar.name, "TINF22B");
+        assert_eq!(calendar.events.len(), 3);
+
+        assert_eq!(
+            CalendarBuilder::new(" ").add_event(event(8)).build(),
+            Err(BuildError::EmptyName)
+        );
+    }
+}
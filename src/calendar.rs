@@ -1,79 +1,995 @@
-use chrono::{NaiveDate, NaiveTime};
-use ics::parameters::TzIDParam;
-use ics::properties::{Description, DtEnd, DtStart, Location, Organizer, RRule, Summary, TzName};
-use ics::{Daylight, Standard, TimeZone};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt;
+use std::io;
 
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use chrono_tz::Tz;
+use ics::components::Property;
+use ics::parameters::{FmtType, TzIDParam};
+use ics::properties::{
+    Attach, Categories, Comment, Description, DtEnd, DtStart, Location, Organizer, Sequence,
+    Summary, Trigger, URL,
+};
+use ics::{escape_text, Alarm};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Calendar {
     pub name: String,
     pub events: Vec<Event>,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Event {
     pub date: NaiveDate,
+    #[serde(
+        serialize_with = "serialize_naive_time",
+        deserialize_with = "deserialize_naive_time"
+    )]
     pub start: NaiveTime,
+    #[serde(
+        serialize_with = "serialize_naive_time",
+        deserialize_with = "deserialize_naive_time"
+    )]
     pub end: NaiveTime,
+    /// Same as `date` unless the event runs past midnight.
+    pub end_date: NaiveDate,
     pub title: String,
-    pub location: Option<String>,
+    pub locations: Vec<String>,
+    /// The persons of the event, see [`Event::instructor`].
+    #[serde(alias = "instructor")]
     pub organizer: Option<String>,
     pub description: Option<String>,
+    /// Extra information hidden in the page, see [`crate::parser::Selectors::event_notes`].
+    pub notes: Option<String>,
+    /// Event type taken from extra CSS classes on the event block, e.g. `category_03`.
+    pub category: Option<String>,
+    /// Link to the details of the event in Rapla itself.
+    pub rapla_url: Option<String>,
+    /// Revision of the event, [`Calendar::diff`] increments it whenever the event changed.
+    #[serde(default)]
+    pub sequence: u32,
+}
+
+// Rapla only shows times with minute precision, so do we.
+const TIME_FORMAT: &str = "%H:%M";
+
+pub(crate) fn serialize_naive_time<S: Serializer>(
+    time: &NaiveTime,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(&time.format(TIME_FORMAT))
+}
+
+pub(crate) fn deserialize_naive_time<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<NaiveTime, D::Error> {
+    let s = String::deserialize(deserializer)?;
+    NaiveTime::parse_from_str(&s, TIME_FORMAT).map_err(serde::de::Error::custom)
+}
+
+/// ICS only has a single `LOCATION`, all locations of an event are joined with this.
+pub(crate) const LOCATION_SEPARATOR: &str = "; ";
+
+/// Backslashes and separators inside of a location are escaped, so [`Calendar::from_ics`]
+/// can split the locations apart again.
+fn join_locations(locations: &[String]) -> String {
+    locations
+        .iter()
+        .map(|location| {
+            location
+                .replace('\\', "\\\\")
+                .replace(LOCATION_SEPARATOR, &format!("\\{LOCATION_SEPARATOR}"))
+        })
+        .collect::<Vec<_>>()
+        .join(LOCATION_SEPARATOR)
+}
+
+const ICS_FOOTER: &str = "END:VCALENDAR\r\n";
+
+fn ics_lines(ics: &str) -> Vec<String> {
+    ics.split_inclusive("\r\n").map(str::to_string).collect()
+}
+
+/// Two overlapping events, the first one from the receiving calendar, the second from the other.
+#[derive(Debug, Clone)]
+pub struct ConflictPair(pub Event, pub Event);
+
+#[derive(Debug, Clone)]
+pub struct ICSOptions {
+    /// Timezone that the times shown by Rapla are interpreted in.
+    pub timezone: Tz,
+    /// Show a reminder this long before each event starts.
+    pub reminder: Option<Duration>,
+    /// Emit all times in UTC instead of referencing a VTIMEZONE.
+    pub utc: bool,
+    /// Creation time stamped onto every event, defaults to the current time.
+    pub dtstamp: Option<DateTime<Utc>>,
+    /// Identifies the software that created the calendar, defaults to [`DEFAULT_PRODID`].
+    pub prodid: Option<String>,
+    /// Readable names for the category classes used by Rapla, e.g. `category_03` to `Klausur`.
+    /// Categories without an entry are emitted as is.
+    pub categories: HashMap<String, String>,
+    /// Also link the event in Rapla as an attachment, some clients only show one of `URL` and `ATTACH`.
+    pub include_attach: bool,
+}
+
+pub const DEFAULT_PRODID: &str = concat!(
+    "-//",
+    env!("CARGO_PKG_NAME"),
+    "//",
+    env!("CARGO_PKG_NAME"),
+    "//EN"
+);
+
+impl Default for ICSOptions {
+    fn default() -> Self {
+        Self {
+            timezone: chrono_tz::Europe::Berlin,
+            reminder: None,
+            utc: false,
+            dtstamp: None,
+            prodid: None,
+            categories: HashMap::new(),
+            include_attach: false,
+        }
+    }
 }
 
 impl Calendar {
     #[must_use]
     pub fn to_ics(&self) -> ics::ICalendar<'_> {
-        let mut cet_standard = Standard::new("19701025T030000", "+0200", "+0100");
-        cet_standard.push(TzName::new("CET"));
-        cet_standard.push(RRule::new("FREQ=YEARLY;BYMONTH=10;BYDAY=-1SU"));
+        self.to_ics_with_options(ICSOptions::default())
+    }
+
+    #[must_use]
+    pub fn to_ics_with_options(&self, mut opts: ICSOptions) -> ics::ICalendar<'_> {
+        let mut icalendar = self.ics_calendar(&mut opts);
+        for event in self {
+            icalendar.add_event(event.to_ics_with_options(&opts));
+        }
 
-        let mut cest_daylight = Daylight::new("19700329T020000", "+0100", "+0200");
-        cest_daylight.push(TzName::new("CEST"));
-        cest_daylight.push(RRule::new("FREQ=YEARLY;BYMONTH=3;BYDAY=-1SU"));
+        icalendar
+    }
 
-        let mut timezone = TimeZone::daylight("Europe/Berlin", cest_daylight);
-        timezone.add_standard(cet_standard);
+    /// Everything in front of the events, fills in the time stamp shared by all of them.
+    pub(crate) fn ics_calendar(&self, opts: &mut ICSOptions) -> ics::ICalendar<'_> {
+        // Use the same time stamp for all events.
+        opts.dtstamp.get_or_insert_with(Utc::now);
 
-        let mut icalendar = ics::ICalendar::new("2.0", &self.name);
-        icalendar.add_timezone(timezone);
+        let prodid = opts
+            .prodid
+            .take()
+            .unwrap_or_else(|| DEFAULT_PRODID.to_string());
 
-        for event in &self.events {
-            icalendar.add_event(event.to_ics());
+        let mut icalendar = ics::ICalendar::new("2.0", prodid);
+        // Non-standard, but used by Apple and Google calendars as the display name.
+        icalendar.push(Property::new("X-WR-CALNAME", escape_text(&self.name)));
+        if !opts.utc {
+            icalendar.add_timezone(crate::timezone::build_vtimezone(opts.timezone));
         }
 
         icalendar
     }
+
+    #[must_use]
+    pub fn to_ics_string(&self) -> String {
+        self.to_ics().to_string()
+    }
+
+    pub fn write_ics<W: io::Write>(&self, writer: W) -> io::Result<()> {
+        self.to_ics().write(writer)
+    }
+
+    /// The lines of [`Calendar::to_ics`] one at a time, each including its line break, so that
+    /// large calendars never have to be rendered into one string.
+    pub fn to_ical_stream(self) -> impl Iterator<Item = String> {
+        self.to_ical_stream_with_options(ICSOptions::default())
+    }
+
+    pub fn to_ical_stream_with_options(self, mut opts: ICSOptions) -> impl Iterator<Item = String> {
+        opts.dtstamp.get_or_insert_with(Utc::now);
+
+        // An empty calendar renders everything in front of the events, including the VTIMEZONE.
+        let header = Calendar {
+            name: self.name,
+            events: Vec::new(),
+        }
+        .to_ics_with_options(opts.clone())
+        .to_string();
+        let header = ics_lines(header.strip_suffix(ICS_FOOTER).unwrap_or(&header));
+
+        let events = self
+            .events
+            .into_iter()
+            .flat_map(move |event| ics_lines(&event.to_ics_with_options(&opts).to_string()));
+
+        header
+            .into_iter()
+            .chain(events)
+            .chain(std::iter::once(ICS_FOOTER.to_string()))
+    }
+
+    /// Times are converted from the given timezone to UTC, no VTIMEZONE component is emitted.
+    #[must_use]
+    pub fn to_ics_utc(&self, tz: Tz) -> ics::ICalendar<'_> {
+        self.to_ics_with_options(ICSOptions {
+            timezone: tz,
+            utc: true,
+            ..ICSOptions::default()
+        })
+    }
+}
+
+impl Calendar {
+    /// Returns a calendar of the same name with only the events matching the predicate.
+    #[must_use]
+    pub fn filter<F: Fn(&Event) -> bool>(&self, f: F) -> Calendar {
+        Calendar {
+            name: self.name.clone(),
+            events: self
+                .events
+                .iter()
+                .filter(|event| f(event))
+                .cloned()
+                .collect(),
+        }
+    }
+
+    pub fn retain<F: Fn(&Event) -> bool>(&mut self, f: F) {
+        self.events.retain(f);
+    }
+
+    /// Splits the calendar into the events matching the predicate and those that don't.
+    #[must_use]
+    pub fn partition<F: Fn(&Event) -> bool>(&self, f: F) -> (Calendar, Calendar) {
+        let (matched, unmatched) = self.events.iter().cloned().partition(|event| f(event));
+        (
+            Calendar {
+                name: self.name.clone(),
+                events: matched,
+            },
+            Calendar {
+                name: self.name.clone(),
+                events: unmatched,
+            },
+        )
+    }
+
+    /// Events whose title, locations or description contain the query, ignoring case.
+    #[must_use]
+    pub fn search(&self, query: &str) -> Vec<&Event> {
+        let query = query.to_lowercase();
+        self.events
+            .iter()
+            .filter(|event| {
+                event.title.to_lowercase().contains(&query)
+                    || event
+                        .locations
+                        .iter()
+                        .any(|location| location.to_lowercase().contains(&query))
+                    || event
+                        .description
+                        .as_ref()
+                        .is_some_and(|description| description.to_lowercase().contains(&query))
+            })
+            .collect()
+    }
+
+    /// Like [`Calendar::search`], but matching a regular expression as is.
+    #[cfg(feature = "regex")]
+    #[must_use]
+    pub fn search_regex(&self, pattern: &regex::Regex) -> Vec<&Event> {
+        self.events
+            .iter()
+            .filter(|event| {
+                pattern.is_match(&event.title)
+                    || event
+                        .locations
+                        .iter()
+                        .any(|location| pattern.is_match(location))
+                    || event
+                        .description
+                        .as_ref()
+                        .is_some_and(|description| pattern.is_match(description))
+            })
+            .collect()
+    }
+
+    #[must_use]
+    pub fn events_on_date(&self, date: NaiveDate) -> Vec<&Event> {
+        self.events
+            .iter()
+            .filter(|event| event.date == date)
+            .collect()
+    }
+
+    /// Events between the two dates, both inclusive.
+    #[must_use]
+    pub fn events_in_range(&self, start: NaiveDate, end: NaiveDate) -> Vec<&Event> {
+        self.events
+            .iter()
+            .filter(|event| (start..=end).contains(&event.date))
+            .collect()
+    }
+
+    #[must_use]
+    pub fn group_by_day(&self) -> BTreeMap<NaiveDate, Vec<&Event>> {
+        let mut groups = BTreeMap::<_, Vec<_>>::new();
+        for event in &self.events {
+            groups.entry(event.date).or_default().push(event);
+        }
+        groups
+    }
+
+    /// Groups events by ISO year and week number.
+    #[must_use]
+    pub fn group_by_week(&self) -> BTreeMap<(i32, u32), Vec<&Event>> {
+        let mut groups = BTreeMap::<_, Vec<_>>::new();
+        for event in &self.events {
+            let week = event.date.iso_week();
+            groups
+                .entry((week.year(), week.week()))
+                .or_default()
+                .push(event);
+        }
+        groups
+    }
+
+    /// Groups events by an arbitrary key, e.g. a normalized title.
+    #[must_use]
+    pub fn group_by<F: Fn(&Event) -> String>(&self, f: F) -> BTreeMap<String, Vec<&Event>> {
+        let mut groups = BTreeMap::<_, Vec<_>>::new();
+        for event in &self.events {
+            groups.entry(f(event)).or_default().push(event);
+        }
+        groups
+    }
+
+    #[must_use]
+    pub fn group_by_title(&self) -> BTreeMap<String, Vec<&Event>> {
+        self.group_by(|event| event.title.clone())
+    }
+
+    /// Like [`Calendar::group_by_title`], but puts e.g. "Mathematik 1 Gruppe A" and
+    /// "Mathematik 1 (Gr. 2)" into the same group as "Mathematik 1".
+    #[must_use]
+    pub fn group_by_title_normalized(&self) -> BTreeMap<String, Vec<&Event>> {
+        self.group_by(|event| strip_group_designator(&event.title).to_string())
+    }
+
+    #[must_use]
+    pub fn into_groups_by_day(self) -> BTreeMap<NaiveDate, Vec<Event>> {
+        let mut groups = BTreeMap::<_, Vec<_>>::new();
+        for event in self.events {
+            groups.entry(event.date).or_default().push(event);
+        }
+        groups
+    }
+
+    #[must_use]
+    pub fn locations(&self) -> BTreeSet<&str> {
+        self.events
+            .iter()
+            .flat_map(|event| &event.locations)
+            .map(String::as_str)
+            .collect()
+    }
+
+    #[must_use]
+    pub fn unique_titles(&self) -> BTreeSet<&str> {
+        self.events
+            .iter()
+            .map(|event| event.title.as_str())
+            .collect()
+    }
+
+    /// Persons of all events, the parser joins several persons of one event with a comma.
+    #[must_use]
+    pub fn instructors(&self) -> BTreeSet<&str> {
+        self.events
+            .iter()
+            .filter_map(|event| event.organizer.as_deref())
+            .flat_map(|organizer| organizer.split(", "))
+            .collect()
+    }
+
+    #[must_use]
+    pub fn events_per_day(&self) -> BTreeMap<NaiveDate, usize> {
+        let mut counts = BTreeMap::new();
+        for event in &self.events {
+            *counts.entry(event.date).or_default() += 1;
+        }
+        counts
+    }
+
+    /// The day with the most events, the earliest one if several days tie.
+    #[must_use]
+    pub fn busiest_day(&self) -> Option<(NaiveDate, usize)> {
+        self.events_per_day()
+            .into_iter()
+            .max_by(|(a_date, a_count), (b_date, b_count)| {
+                a_count.cmp(b_count).then(b_date.cmp(a_date))
+            })
+    }
+
+    /// The day with the fewest events, days without any events are not considered.
+    #[must_use]
+    pub fn lightest_day(&self) -> Option<(NaiveDate, usize)> {
+        self.events_per_day()
+            .into_iter()
+            .min_by_key(|(_, count)| *count)
+    }
+
+    #[must_use]
+    pub fn total_duration(&self) -> Duration {
+        self.events.iter().map(Event::duration).sum()
+    }
+
+    /// Total duration of the events between the two dates, both inclusive.
+    #[must_use]
+    pub fn total_duration_in_range(&self, start: NaiveDate, end: NaiveDate) -> Duration {
+        self.events_in_range(start, end)
+            .into_iter()
+            .map(Event::duration)
+            .sum()
+    }
+
+    /// Total duration of the events per ISO year and week number.
+    #[must_use]
+    pub fn weekly_hours(&self) -> BTreeMap<(i32, u32), Duration> {
+        self.group_by_week()
+            .into_iter()
+            .map(|(week, events)| (week, events.into_iter().map(Event::duration).sum()))
+            .collect()
+    }
+
+    /// Total duration in hours per title, e.g. for reporting attendance at the end of a semester.
+    #[must_use]
+    pub fn hours_per_course(&self) -> BTreeMap<String, f64> {
+        self.group_by_title()
+            .into_iter()
+            .map(|(title, events)| {
+                let duration: Duration = events.into_iter().map(Event::duration).sum();
+                (title, duration.num_seconds() as f64 / 3600.0)
+            })
+            .collect()
+    }
+
+    /// Averaged over the weeks that have at least one event.
+    #[must_use]
+    pub fn average_weekly_hours(&self) -> Duration {
+        let weekly_hours = self.weekly_hours();
+        match i32::try_from(weekly_hours.len()) {
+            Ok(0) | Err(_) => Duration::zero(),
+            Ok(weeks) => weekly_hours.into_values().sum::<Duration>() / weeks,
+        }
+    }
+
+    /// Sorts events chronologically by date and start time, see the [`Ord`] implementation of [`Event`].
+    pub fn sort(&mut self) {
+        self.events.sort();
+    }
+
+    /// Sorts events chronologically by date and end time.
+    pub fn sort_by_end(&mut self) {
+        self.events.sort_by_key(Event::end_datetime);
+    }
+
+    /// Whether events are already in the order established by [`Calendar::sort`].
+    #[must_use]
+    pub fn is_sorted(&self) -> bool {
+        self.events
+            .is_sorted_by_key(|event| (event.date, event.start))
+    }
+
+    /// Removes consecutive duplicate events, best used after [`Calendar::sort`].
+    pub fn dedup(&mut self) {
+        self.events.dedup();
+    }
+
+    /// Removes all duplicate events regardless of their order, keeping the first occurrence.
+    pub fn dedup_unsorted(&mut self) {
+        let keep = {
+            let mut seen = HashSet::new();
+            self.events
+                .iter()
+                .map(|event| seen.insert(event))
+                .collect::<Vec<_>>()
+        };
+
+        let mut keep = keep.into_iter();
+        self.events.retain(|_| keep.next().unwrap_or(true));
+    }
+
+    /// Merges lectures that Rapla split into back to back blocks, i.e. events on the same day with the
+    /// same title and locations where one ends exactly when the other starts. The merged event keeps
+    /// the remaining details of the earlier block. Sorts the calendar, see [`Calendar::sort`].
+    pub fn compact(&mut self) {
+        self.sort();
+
+        let mut compacted: Vec<Event> = Vec::with_capacity(self.events.len());
+        for event in self.events.drain(..) {
+            let previous = compacted
+                .iter_mut()
+                .rev()
+                .take_while(|previous| previous.date == event.date)
+                .find(|previous| {
+                    previous.end_date == event.date
+                        && previous.end == event.start
+                        && previous.title == event.title
+                        && previous.locations == event.locations
+                });
+
+            match previous {
+                Some(previous) => {
+                    previous.end = event.end;
+                    previous.end_date = event.end_date;
+                }
+                None => compacted.push(event),
+            }
+        }
+
+        self.events = compacted;
+    }
+
+    /// Trims leading and trailing whitespace, including non-breaking spaces, from the text of every
+    /// event. Locations, descriptions, notes and organizers that end up empty are dropped.
+    pub fn normalize(&mut self) {
+        fn trim(value: &mut Option<String>) {
+            *value = value
+                .take()
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty());
+        }
+
+        for event in &mut self.events {
+            event.title = event.title.trim().to_string();
+            event.locations = event
+                .locations
+                .iter()
+                .map(|location| location.trim())
+                .filter(|location| !location.is_empty())
+                .map(str::to_string)
+                .collect();
+            trim(&mut event.organizer);
+            trim(&mut event.description);
+            trim(&mut event.notes);
+        }
+    }
+
+    /// A copy of the calendar with whitespace trimmed, see [`Calendar::normalize`].
+    #[must_use]
+    pub fn normalized(&self) -> Calendar {
+        let mut calendar = self.clone();
+        calendar.normalize();
+        calendar
+    }
+
+    /// Inserts the event at its place in the order established by [`Calendar::sort`], which the
+    /// events are expected to be in already. Returns `false` without inserting if an event with the same
+    /// date, times and title exists.
+    pub fn add_event(&mut self, event: Event) -> bool {
+        fn key(event: &Event) -> (NaiveDate, NaiveTime, NaiveTime, &str) {
+            (event.date, event.start, event.end, &event.title)
+        }
+
+        let idx = self
+            .events
+            .partition_point(|existing| key(existing) < key(&event));
+        if self
+            .events
+            .get(idx)
+            .is_some_and(|existing| key(existing) == key(&event))
+        {
+            return false;
+        }
+
+        self.events.insert(idx, event);
+        true
+    }
+
+    /// Removes the first event equal to the given one, returns whether there was one.
+    pub fn remove_event(&mut self, event: &Event) -> bool {
+        match self.events.iter().position(|existing| existing == event) {
+            Some(idx) => {
+                self.events.remove(idx);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Removes all events matching the predicate, returns how many were removed.
+    pub fn remove_events_where<F: Fn(&Event) -> bool>(&mut self, predicate: F) -> usize {
+        let len = self.events.len();
+        self.events.retain(|event| !predicate(event));
+        len - self.events.len()
+    }
+
+    /// The first event starting strictly after the given time, found with a binary search.
+    /// The calendar has to be sorted, see [`Calendar::sort`], parsed calendars aren't.
+    #[must_use]
+    pub fn next_event(&self, from: NaiveDateTime) -> Option<&Event> {
+        debug_assert!(self.is_sorted(), "calendar should be sorted");
+        let idx = self
+            .events
+            .partition_point(|event| event.start_datetime() <= from);
+        self.events.get(idx)
+    }
+
+    /// The last event starting before the given time, like [`Calendar::next_event`] in reverse.
+    #[must_use]
+    pub fn prev_event(&self, from: NaiveDateTime) -> Option<&Event> {
+        debug_assert!(self.is_sorted(), "calendar should be sorted");
+        let idx = self
+            .events
+            .partition_point(|event| event.start_datetime() < from);
+        idx.checked_sub(1).and_then(|idx| self.events.get(idx))
+    }
+
+    /// All events in progress at the given time, usually just one unless events overlap.
+    #[must_use]
+    pub fn current_event(&self, at: NaiveDateTime) -> Vec<&Event> {
+        self.events
+            .iter()
+            .filter(|event| event.start_datetime() <= at && at < event.end_datetime())
+            .collect()
+    }
+
+    /// Gaps between the events of the day within the given working hours, in chronological order.
+    #[must_use]
+    pub fn free_slots(
+        &self,
+        day: NaiveDate,
+        working_start: NaiveTime,
+        working_end: NaiveTime,
+    ) -> Vec<(NaiveTime, NaiveTime)> {
+        let window_start = day.and_time(working_start);
+        let window_end = day.and_time(working_end);
+
+        // Clip to the window first, this also covers events that started on the day before.
+        let mut busy = self
+            .events
+            .iter()
+            .filter(|event| {
+                event.start_datetime() < window_end && event.end_datetime() > window_start
+            })
+            .map(|event| {
+                (
+                    event.start_datetime().max(window_start),
+                    event.end_datetime().min(window_end),
+                )
+            })
+            .collect::<Vec<_>>();
+        busy.sort();
+
+        let mut slots = Vec::new();
+        let mut free_from = window_start;
+        for (start, end) in busy {
+            if start > free_from {
+                slots.push((free_from.time(), start.time()));
+            }
+            free_from = free_from.max(end);
+        }
+        if free_from < window_end {
+            slots.push((free_from.time(), working_end));
+        }
+
+        slots
+    }
+
+    /// Pairs of events that overlap each other, see [`Event::overlaps`].
+    #[must_use]
+    pub fn conflicts(&self) -> Vec<(&Event, &Event)> {
+        let mut sorted = self.events.iter().collect::<Vec<_>>();
+        sorted.sort_by_key(|event| (event.date, event.start));
+
+        // Sweep through the events in order, only keeping track of those that haven't ended yet.
+        let mut conflicts = Vec::new();
+        let mut active: Vec<&Event> = Vec::new();
+        for event in sorted {
+            active.retain(|other| other.end_datetime() > event.start_datetime());
+            conflicts.extend(
+                active
+                    .iter()
+                    .filter(|other| other.overlaps(event))
+                    .map(|other| (*other, event)),
+            );
+            active.push(event);
+        }
+
+        conflicts
+    }
+
+    /// Appends the events of the other calendar, keeping this calendar's name.
+    #[must_use]
+    pub fn merge(mut self, other: Calendar) -> Calendar {
+        self.events.extend(other.events);
+        self
+    }
+
+    /// Like [`Calendar::merge`], but drops events with the same date, times and title as an earlier one.
+    #[must_use]
+    pub fn merge_dedup(self, other: Calendar) -> Calendar {
+        let mut seen = HashSet::new();
+        let mut merged = self.merge(other);
+        merged
+            .events
+            .retain(|event| seen.insert((event.date, event.start, event.end, event.title.clone())));
+        merged
+    }
+
+    /// Like [`Calendar::merge`], but fails with all pairs of events that overlap between the two calendars.
+    pub fn merge_checked(self, other: Calendar) -> Result<Calendar, Vec<ConflictPair>> {
+        let conflicts = self
+            .events
+            .iter()
+            .flat_map(|ours| {
+                other
+                    .events
+                    .iter()
+                    .filter(|theirs| ours.overlaps(theirs))
+                    .map(|theirs| ConflictPair(ours.clone(), theirs.clone()))
+            })
+            .collect::<Vec<_>>();
+
+        if conflicts.is_empty() {
+            Ok(self.merge(other))
+        } else {
+            Err(conflicts)
+        }
+    }
+
+    /// Events in the given ISO week, same as the week numbers shown by Rapla.
+    #[must_use]
+    pub fn events_in_week(&self, year: i32, week: u32) -> Vec<&Event> {
+        self.events
+            .iter()
+            .filter(|event| {
+                let iso_week = event.date.iso_week();
+                iso_week.year() == year && iso_week.week() == week
+            })
+            .collect()
+    }
+}
+
+const GROUP_MARKERS: [&str; 5] = ["gruppe", "group", "grp.", "gr.", "gr"];
+
+/// Strips a trailing group designator such as "Gruppe A", "Gr. 1" or "(Grp. B/C)" from a title.
+fn strip_group_designator(title: &str) -> &str {
+    let title = title.trim_end();
+    let Some((head, value)) = title.rsplit_once(' ') else {
+        return title;
+    };
+    let (head, marker) = head.rsplit_once(' ').unwrap_or(("", head));
+
+    let marker = marker.strip_prefix('(').unwrap_or(marker).to_lowercase();
+    let value = value.strip_suffix(')').unwrap_or(value);
+    let is_designator = GROUP_MARKERS.contains(&marker.as_str())
+        && (1..=3).contains(&value.chars().filter(char::is_ascii_alphanumeric).count())
+        && value.chars().all(|c| c.is_ascii_alphanumeric() || c == '/');
+
+    let head = head.trim_end_matches([' ', '-', '\u{2013}', ',', ':', '/']);
+    if is_designator && !head.is_empty() {
+        head
+    } else {
+        title
+    }
+}
+
+/// Orders by date, start, end and title. The remaining fields only break ties to stay consistent with [`Eq`].
+impl Ord for Event {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.date, self.start, self.end, &self.title)
+            .cmp(&(other.date, other.start, other.end, &other.title))
+            .then_with(|| {
+                (
+                    &self.locations,
+                    &self.organizer,
+                    &self.description,
+                    &self.notes,
+                    &self.category,
+                    &self.rapla_url,
+                    self.sequence,
+                )
+                    .cmp(&(
+                        &other.locations,
+                        &other.organizer,
+                        &other.description,
+                        &other.notes,
+                        &other.category,
+                        &other.rapla_url,
+                        other.sequence,
+                    ))
+            })
+    }
+}
+
+impl PartialOrd for Event {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Calendar {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+        for event in &self.events {
+            write!(f, "\n{event}")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {}\u{2013}{}  {}",
+            self.date.format("%Y-%m-%d"),
+            self.start.format("%H:%M"),
+            self.end.format("%H:%M"),
+            self.title
+        )?;
+
+        if !self.locations.is_empty() {
+            write!(f, "  ({})", self.locations.join(", "))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl IntoIterator for Calendar {
+    type Item = Event;
+    type IntoIter = std::vec::IntoIter<Event>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.events.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Calendar {
+    type Item = &'a Event;
+    type IntoIter = std::slice::Iter<'a, Event>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.events.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a mut Calendar {
+    type Item = &'a mut Event;
+    type IntoIter = std::slice::IterMut<'a, Event>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.events.iter_mut()
+    }
+}
+
+impl Extend<Event> for Calendar {
+    fn extend<T: IntoIterator<Item = Event>>(&mut self, iter: T) {
+        self.events.extend(iter);
+    }
+}
+
+/// Collected calendars are left without a name.
+impl FromIterator<Event> for Calendar {
+    fn from_iter<T: IntoIterator<Item = Event>>(iter: T) -> Self {
+        Self {
+            name: String::new(),
+            events: iter.into_iter().collect(),
+        }
+    }
 }
 
 impl Event {
     #[must_use]
-    pub fn to_ics(&self) -> ics::Event<'_> {
-        let start = format!(
-            "{}T{}00",
-            self.date.format("%Y%m%d"),
-            self.start.format("%H%M")
+    pub fn start_datetime(&self) -> NaiveDateTime {
+        self.date.and_time(self.start)
+    }
+
+    #[must_use]
+    pub fn end_datetime(&self) -> NaiveDateTime {
+        self.end_date.and_time(self.end)
+    }
+
+    #[must_use]
+    pub fn duration(&self) -> Duration {
+        self.end_datetime() - self.start_datetime()
+    }
+
+    /// Whoever gives the lecture, Rapla lists them as persons of the event. Several instructors are
+    /// joined with a comma. Emitted as `ORGANIZER` in ICS output.
+    #[must_use]
+    pub fn instructor(&self) -> Option<&str> {
+        self.organizer.as_deref()
+    }
+
+    /// Whether the times of both events intersect.
+    #[must_use]
+    pub fn overlaps(&self, other: &Event) -> bool {
+        self.start_datetime() < other.end_datetime() && other.start_datetime() < self.end_datetime()
+    }
+
+    /// Stable identifier derived from the event's contents.
+    #[cfg(feature = "uuid")]
+    #[must_use]
+    pub fn uid(&self) -> String {
+        // UUID v5 of "https://github.com/satoqz/rapla-ical-proxy" in the URL namespace.
+        const NAMESPACE: uuid::Uuid = uuid::Uuid::from_u128(0x21d29c90_dbfd_55e2_a1e6_d57c42ee1cc6);
+
+        let name = format!(
+            "{}\0{}\0{}\0{}\0{}",
+            self.date,
+            self.start,
+            self.end,
+            self.title,
+            self.locations.join(", ")
         );
 
-        let end = format!(
-            "{}T{}00",
+        uuid::Uuid::new_v5(&NAMESPACE, name.as_bytes()).to_string()
+    }
+
+    /// Identifier derived from the event's start and title.
+    #[cfg(not(feature = "uuid"))]
+    #[must_use]
+    pub fn uid(&self) -> String {
+        format!(
+            "{}T{}00_{}",
             self.date.format("%Y%m%d"),
-            self.end.format("%H%M")
-        );
+            self.start.format("%H%M"),
+            self.title.replace(' ', "-")
+        )
+    }
+
+    #[must_use]
+    pub fn to_ics(&self) -> ics::Event<'_> {
+        self.to_ics_with_options(&ICSOptions::default())
+    }
 
-        let id = format!("{}_{}", start, self.title.replace(' ', "-"));
+    pub(crate) fn format_local(local: NaiveDateTime) -> String {
+        local.format("%Y%m%dT%H%M%S").to_string()
+    }
 
-        let mut ics_event = ics::Event::new(id, start.clone());
+    pub(crate) fn format_utc(local: NaiveDateTime, tz: Tz) -> String {
+        let utc = crate::timezone::localize(local, tz).naive_utc();
+        utc.format("%Y%m%dT%H%M%SZ").to_string()
+    }
 
-        let mut dtstart = DtStart::new(start);
-        dtstart.add(TzIDParam::new("Europe/Berlin"));
+    #[must_use]
+    pub fn to_ics_with_options(&self, opts: &ICSOptions) -> ics::Event<'_> {
+        let (start, end) = if opts.utc {
+            (
+                Self::format_utc(self.start_datetime(), opts.timezone),
+                Self::format_utc(self.end_datetime(), opts.timezone),
+            )
+        } else {
+            (
+                Self::format_local(self.start_datetime()),
+                Self::format_local(self.end_datetime()),
+            )
+        };
+
+        let dtstamp = opts.dtstamp.unwrap_or_else(Utc::now);
+        let mut ics_event =
+            ics::Event::new(self.uid(), dtstamp.format("%Y%m%dT%H%M%SZ").to_string());
 
+        let mut dtstart = DtStart::new(start);
         let mut dtend = DtEnd::new(end);
-        dtend.add(TzIDParam::new("Europe/Berlin"));
+        if !opts.utc {
+            dtstart.add(TzIDParam::new(opts.timezone.name()));
+            dtend.add(TzIDParam::new(opts.timezone.name()));
+        }
 
         ics_event.push(dtstart);
         ics_event.push(dtend);
-        ics_event.push(Summary::new(&self.title));
+        ics_event.push(Summary::new(escape_text(&self.title)));
 
-        if let Some(location) = &self.location {
-            ics_event.push(Location::new(location));
+        if !self.locations.is_empty() {
+            ics_event.push(Location::new(escape_text(join_locations(&self.locations))));
         }
 
         if let Some(organizer) = &self.organizer {
@@ -81,9 +997,493 @@ impl Event {
         }
 
         if let Some(description) = &self.description {
-            ics_event.push(Description::new(description));
+            ics_event.push(Description::new(escape_text(description)));
+        }
+
+        if let Some(notes) = &self.notes {
+            ics_event.push(Comment::new(escape_text(notes)));
+        }
+
+        if let Some(category) = &self.category {
+            let category = opts.categories.get(category).unwrap_or(category);
+            ics_event.push(Categories::new(escape_text(category.clone())));
+        }
+
+        if let Some(rapla_url) = &self.rapla_url {
+            ics_event.push(URL::new(rapla_url));
+            if opts.include_attach {
+                let mut attach = Attach::new(rapla_url);
+                attach.add(FmtType::new("text/html"));
+                ics_event.push(attach);
+            }
+        }
+
+        // Zero is the default, leaving it out keeps the output of unchanged events as it was.
+        if self.sequence > 0 {
+            ics_event.push(Sequence::new(self.sequence.to_string()));
+        }
+
+        if let Some(reminder) = opts.reminder {
+            let trigger = Trigger::new(format!("-PT{}M", reminder.num_minutes()));
+            ics_event.add_alarm(Alarm::display(trigger, Description::new("Reminder")));
         }
 
         ics_event
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use chrono::{Datelike, Duration, NaiveDate, NaiveTime, TimeZone, Timelike, Utc};
+
+    use super::{Calendar, Event, ICSOptions};
+
+    fn event(day: u32, start: u32, end: u32, title: &str) -> Event {
+        let date = NaiveDate::from_ymd_opt(2023, 10, day).unwrap();
+        Event {
+            date,
+            start: NaiveTime::from_hms_opt(start, 0, 0).unwrap(),
+            end: NaiveTime::from_hms_opt(end, 0, 0).unwrap(),
+            end_date: date,
+            title: title.to_string(),
+            locations: Vec::new(),
+            organizer: None,
+            description: None,
+            notes: None,
+            category: None,
+            rapla_url: None,
+            sequence: 0,
+        }
+    }
+
+    fn calendar(events: Vec<Event>) -> Calendar {
+        Calendar {
+            name: "TINF22B".to_string(),
+            events,
+        }
+    }
+
+    #[test]
+    fn test_display() {
+        let mut event = event(23, 8, 10, "Mathematik 1");
+        event.locations.push("HS 101".to_string());
+        assert_eq!(
+            calendar(vec![event]).to_string(),
+            "TINF22B\n2023-10-23 08:00\u{2013}10:00  Mathematik 1  (HS 101)"
+        );
+    }
+
+    #[test]
+    fn test_event_hash_consistent_with_eq() {
+        let mut other_room = event(23, 8, 10, "A");
+        other_room.locations.push("HS 101".to_string());
+
+        let events = HashSet::from([event(23, 8, 10, "A"), event(23, 8, 10, "A"), other_room]);
+        assert_eq!(events.len(), 2);
+        assert!(events.contains(&event(23, 8, 10, "A")));
+    }
+
+    #[test]
+    fn test_serde_round_trip() {
+        let calendar = calendar(vec![event(23, 8, 10, "A")]);
+        let json = serde_json::to_string(&calendar).unwrap();
+        assert!(json.contains(r#""start":"08:00","end":"10:00""#));
+        assert_eq!(serde_json::from_str::<Calendar>(&json).unwrap(), calendar);
+    }
+
+    #[test]
+    fn test_instructor() {
+        let mut lecture = event(23, 8, 10, "A");
+        lecture.organizer = Some("Müller, Schmidt".to_string());
+        assert_eq!(lecture.instructor(), Some("Müller, Schmidt"));
+
+        let calendar = calendar(vec![lecture]);
+        let json = serde_json::to_string(&calendar).unwrap();
+        assert!(json.contains(r#""organizer":"Müller, Schmidt""#));
+        let renamed = json.replace(r#""organizer""#, r#""instructor""#);
+        assert_eq!(
+            serde_json::from_str::<Calendar>(&renamed).unwrap(),
+            calendar
+        );
+
+        let ics = calendar.to_ics().to_string();
+        assert!(ics.contains("ORGANIZER:Müller, Schmidt\r\n"));
+    }
+
+    #[test]
+    fn test_to_ics_utc() {
+        let calendar = calendar(vec![event(23, 8, 10, "A")]);
+        let ics = calendar.to_ics_utc(chrono_tz::Europe::Berlin).to_string();
+        assert!(ics.contains("DTSTAMP:"));
+        assert!(ics.contains("DTSTART:20231023T060000Z\r\n"));
+        assert!(ics.contains("DTEND:20231023T080000Z\r\n"));
+        assert!(!ics.contains("VTIMEZONE"));
+        assert!(ics.contains("PRODID:-//rapla-ical-proxy//rapla-ical-proxy//EN\r\n"));
+        assert!(ics.contains("X-WR-CALNAME:TINF22B\r\n"));
+    }
+
+    #[test]
+    fn test_to_ics_dtstamp() {
+        let opts = ICSOptions {
+            dtstamp: Some(Utc.with_ymd_and_hms(2023, 10, 1, 12, 0, 0).unwrap()),
+            ..ICSOptions::default()
+        };
+        let ics = event(23, 8, 10, "A").to_ics_with_options(&opts).to_string();
+        assert!(ics.contains("DTSTAMP:20231001T120000Z\r\n"));
+    }
+
+    #[test]
+    fn test_collect_and_extend() {
+        let mut collected = [event(23, 8, 10, "A")].into_iter().collect::<Calendar>();
+        assert_eq!(collected.name, "");
+
+        collected.extend(calendar(vec![event(24, 8, 10, "B")]));
+        assert_eq!(collected.events.len(), 2);
+    }
+
+    #[test]
+    fn test_search() {
+        let mut lab = event(23, 8, 10, "Labor");
+        lab.locations.push("Raum A".to_string());
+        let calendar = calendar(vec![event(23, 10, 12, "Mathematik"), lab]);
+
+        fn titles(events: Vec<&Event>) -> Vec<&str> {
+            events
+                .into_iter()
+                .map(|event| event.title.as_str())
+                .collect()
+        }
+        assert_eq!(titles(calendar.search("MATHE")), ["Mathematik"]);
+        assert_eq!(titles(calendar.search("raum")), ["Labor"]);
+        assert!(calendar.search("Physik").is_empty());
+    }
+
+    #[test]
+    fn test_busiest_and_lightest_day() {
+        let calendar = calendar(vec![
+            event(23, 8, 10, "A"),
+            event(24, 8, 10, "B"),
+            event(24, 10, 12, "C"),
+            event(25, 8, 10, "D"),
+            event(26, 8, 10, "E"),
+            event(26, 10, 12, "F"),
+        ]);
+        let date = |day| NaiveDate::from_ymd_opt(2023, 10, day).unwrap();
+
+        assert_eq!(calendar.busiest_day(), Some((date(24), 2)));
+        assert_eq!(calendar.lightest_day(), Some((date(23), 1)));
+        assert_eq!(Calendar::default().busiest_day(), None);
+    }
+
+    #[test]
+    fn test_weekly_hours() {
+        let calendar = calendar(vec![
+            event(23, 8, 10, "A"),
+            event(24, 8, 12, "B"),
+            event(30, 8, 10, "C"),
+        ]);
+
+        let weekly_hours = calendar.weekly_hours();
+        assert_eq!(weekly_hours[&(2023, 43)], Duration::hours(6));
+        assert_eq!(weekly_hours[&(2023, 44)], Duration::hours(2));
+        assert_eq!(calendar.average_weekly_hours(), Duration::hours(4));
+    }
+
+    #[test]
+    fn test_hours_per_course() {
+        let mut short = event(26, 9, 9, "Labor");
+        short.end = NaiveTime::from_hms_opt(9, 30, 0).unwrap();
+        let calendar = calendar(vec![
+            event(23, 8, 10, "Mathematik"),
+            event(24, 13, 14, "Labor"),
+            event(25, 8, 11, "Mathematik"),
+            short,
+        ]);
+
+        let hours = calendar.hours_per_course();
+        assert!(hours.keys().eq(["Labor", "Mathematik"]));
+        assert_eq!(hours["Labor"], 1.5);
+        assert_eq!(hours["Mathematik"], 5.0);
+    }
+
+    #[test]
+    fn test_unique_values() {
+        let mut a = event(23, 8, 10, "Mathematik");
+        a.locations = vec!["B 2.02".to_string(), "A 1.01".to_string()];
+        a.organizer = Some("Müller, Schmidt".to_string());
+        let mut b = event(24, 8, 10, "Mathematik");
+        b.locations = vec!["A 1.01".to_string()];
+        b.organizer = Some("Müller".to_string());
+        let calendar = calendar(vec![a, b, event(25, 8, 10, "Labor")]);
+
+        assert!(calendar.locations().into_iter().eq(["A 1.01", "B 2.02"]));
+        assert!(calendar
+            .unique_titles()
+            .into_iter()
+            .eq(["Labor", "Mathematik"]));
+        assert!(calendar.instructors().into_iter().eq(["Müller", "Schmidt"]));
+    }
+
+    #[test]
+    fn test_group_by_title() {
+        let calendar = calendar(vec![
+            event(23, 8, 10, "Mathematik 1 Gruppe A"),
+            event(24, 8, 10, "Mathematik 1 \u{2013} Gr. 2"),
+            event(25, 8, 10, "Mathematik 1 (Grp. B/C)"),
+            event(26, 8, 10, "Mathematik 1"),
+            event(27, 8, 10, "Gruppe A"),
+        ]);
+
+        assert_eq!(calendar.group_by_title().len(), 5);
+
+        let groups = calendar.group_by_title_normalized();
+        assert!(groups.keys().eq(["Gruppe A", "Mathematik 1"]));
+        assert_eq!(groups["Mathematik 1"].len(), 4);
+    }
+
+    #[test]
+    fn test_free_slots() {
+        let calendar = calendar(vec![
+            event(23, 10, 12, "A"),
+            event(23, 11, 13, "B"),
+            event(23, 15, 16, "C"),
+            event(23, 17, 20, "D"),
+            event(24, 8, 18, "E"),
+        ]);
+        let time = |hour| NaiveTime::from_hms_opt(hour, 0, 0).unwrap();
+
+        assert_eq!(
+            calendar.free_slots(
+                NaiveDate::from_ymd_opt(2023, 10, 23).unwrap(),
+                time(8),
+                time(18)
+            ),
+            [
+                (time(8), time(10)),
+                (time(13), time(15)),
+                (time(16), time(17))
+            ]
+        );
+        assert!(calendar
+            .free_slots(
+                NaiveDate::from_ymd_opt(2023, 10, 24).unwrap(),
+                time(8),
+                time(18)
+            )
+            .is_empty());
+    }
+
+    #[test]
+    fn test_add_event() {
+        let mut calendar = calendar(vec![event(23, 8, 10, "A"), event(25, 8, 10, "C")]);
+
+        assert!(calendar.add_event(event(24, 8, 10, "B")));
+        assert!(calendar.add_event(event(23, 8, 10, "B")));
+        let mut moved = event(24, 8, 10, "B");
+        moved.locations.push("HS 101".to_string());
+        assert!(!calendar.add_event(moved));
+
+        assert!(calendar.is_sorted());
+        assert_eq!(
+            calendar
+                .events
+                .iter()
+                .map(|event| (event.date.day(), event.title.as_str()))
+                .collect::<Vec<_>>(),
+            [(23, "A"), (23, "B"), (24, "B"), (25, "C")]
+        );
+    }
+
+    #[test]
+    fn test_remove_event() {
+        let mut calendar = calendar(vec![
+            event(23, 8, 10, "A"),
+            event(23, 8, 10, "A"),
+            event(24, 8, 10, "B"),
+            event(25, 8, 10, "C"),
+        ]);
+
+        assert!(calendar.remove_event(&event(23, 8, 10, "A")));
+        assert!(!calendar.remove_event(&event(23, 8, 10, "B")));
+        assert_eq!(calendar.events.len(), 3);
+
+        assert_eq!(calendar.remove_events_where(|event| event.title != "C"), 2);
+        assert_eq!(calendar.events, [event(25, 8, 10, "C")]);
+    }
+
+    #[test]
+    fn test_compact() {
+        let mut elsewhere = event(23, 12, 13, "A");
+        elsewhere.locations.push("HS 101".to_string());
+        let mut calendar = calendar(vec![
+            event(23, 10, 12, "A"),
+            event(23, 8, 10, "A"),
+            event(23, 10, 11, "B"),
+            elsewhere,
+            event(23, 13, 14, "A"),
+            event(24, 10, 12, "A"),
+        ]);
+
+        calendar.compact();
+        assert_eq!(
+            calendar
+                .events
+                .iter()
+                .map(|event| (event.date.day(), event.start.hour(), event.end.hour()))
+                .collect::<Vec<_>>(),
+            [
+                (23, 8, 12),
+                (23, 10, 11),
+                (23, 12, 13),
+                (23, 13, 14),
+                (24, 10, 12)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_normalize() {
+        let mut messy = event(23, 8, 10, "\u{a0}Mathematik 1 ");
+        messy.locations = vec![" HS 101\n".to_string(), "\u{a0}".to_string()];
+        messy.organizer = Some("  Prof. Dr. Müller".to_string());
+        messy.description = Some(" ".to_string());
+        let mut calendar = calendar(vec![messy]);
+
+        let normalized = calendar.normalized();
+        calendar.normalize();
+        assert_eq!(calendar, normalized);
+
+        let event = &calendar.events[0];
+        assert_eq!(event.title, "Mathematik 1");
+        assert_eq!(event.locations, ["HS 101"]);
+        assert_eq!(event.organizer.as_deref(), Some("Prof. Dr. Müller"));
+        assert_eq!(event.description, None);
+    }
+
+    #[test]
+    fn test_next_prev_and_current_event() {
+        let mut calendar = calendar(vec![
+            event(24, 8, 10, "C"),
+            event(23, 8, 10, "A"),
+            event(23, 12, 14, "B"),
+        ]);
+        let at = |day, hour| {
+            NaiveDate::from_ymd_opt(2023, 10, day)
+                .unwrap()
+                .and_hms_opt(hour, 0, 0)
+                .unwrap()
+        };
+
+        fn title(event: Option<&Event>) -> Option<&str> {
+            event.map(|event| event.title.as_str())
+        }
+
+        calendar.sort();
+        assert_eq!(title(calendar.next_event(at(23, 8))), Some("B"));
+        assert_eq!(title(calendar.next_event(at(23, 7))), Some("A"));
+        assert_eq!(title(calendar.next_event(at(24, 8))), None);
+        assert_eq!(title(calendar.prev_event(at(23, 12))), Some("A"));
+        assert_eq!(title(calendar.prev_event(at(25, 0))), Some("C"));
+        assert_eq!(title(calendar.prev_event(at(23, 8))), None);
+
+        assert_eq!(calendar.current_event(at(23, 9)).len(), 1);
+        assert!(calendar.current_event(at(23, 10)).is_empty());
+    }
+
+    #[test]
+    fn test_to_ics_categories() {
+        let mut exam = event(23, 8, 10, "A");
+        exam.category = Some("category_03".to_string());
+        let mut other = event(24, 8, 10, "B");
+        other.category = Some("category_04".to_string());
+
+        let opts = ICSOptions {
+            categories: [("category_03".to_string(), "Klausur".to_string())].into(),
+            ..ICSOptions::default()
+        };
+        let ics = calendar(vec![exam, other])
+            .to_ics_with_options(opts)
+            .to_string();
+        assert!(ics.contains("CATEGORIES:Klausur\r\n"));
+        assert!(ics.contains("CATEGORIES:category_04\r\n"));
+    }
+
+    #[test]
+    fn test_to_ical_stream() {
+        let mut long = event(24, 8, 10, "B");
+        long.description = Some("Lorem ipsum dolor sit amet. ".repeat(10));
+        let calendar = calendar(vec![event(23, 8, 10, "A"), long]);
+        let opts = ICSOptions {
+            dtstamp: Some(Utc.with_ymd_and_hms(2023, 10, 1, 0, 0, 0).unwrap()),
+            reminder: Some(Duration::minutes(15)),
+            ..ICSOptions::default()
+        };
+
+        let ics = calendar.to_ics_with_options(opts.clone()).to_string();
+        let lines = calendar
+            .to_ical_stream_with_options(opts)
+            .collect::<Vec<_>>();
+        assert!(lines.iter().all(|line| line.ends_with("\r\n")));
+        assert_eq!(lines.first().unwrap(), "BEGIN:VCALENDAR\r\n");
+        assert_eq!(lines.concat(), ics);
+    }
+
+    #[test]
+    fn test_to_ics_sequence() {
+        let mut changed = event(23, 8, 10, "A");
+        changed.sequence = 2;
+        let ics = calendar(vec![changed, event(24, 8, 10, "B")])
+            .to_ics()
+            .to_string();
+        assert_eq!(ics.matches("SEQUENCE:").count(), 1);
+        assert!(ics.contains("SEQUENCE:2\r\n"));
+    }
+
+    #[test]
+    fn test_to_ics_attach() {
+        let mut linked = event(23, 8, 10, "A");
+        linked.rapla_url = Some("https://rapla.dhbw.de/rapla/event?id=1".to_string());
+        let calendar = calendar(vec![linked]);
+
+        let ics = calendar.to_ics().to_string();
+        assert!(ics.contains("URL:https://rapla.dhbw.de/rapla/event?id=1\r\n"));
+        assert!(!ics.contains("ATTACH"));
+
+        let opts = ICSOptions {
+            include_attach: true,
+            ..ICSOptions::default()
+        };
+        let ics = calendar.to_ics_with_options(opts).to_string();
+        assert!(ics.contains("ATTACH;FMTTYPE=text/html:https://rapla.dhbw.de/rapla/event?id=1\r\n"));
+    }
+
+    #[test]
+    fn test_conflicts() {
+        let calendar = calendar(vec![
+            event(23, 11, 13, "B"),
+            event(23, 10, 12, "A"),
+            event(23, 13, 14, "C"),
+            event(24, 10, 12, "D"),
+        ]);
+
+        let conflicts = calendar
+            .conflicts()
+            .into_iter()
+            .map(|(a, b)| (a.title.as_str(), b.title.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(conflicts, [("A", "B")]);
+    }
+
+    #[test]
+    fn test_past_midnight() {
+        let mut late = event(23, 22, 1, "Labor");
+        late.end_date = late.date.succ_opt().unwrap();
+        assert_eq!(late.duration(), Duration::hours(3));
+        assert!(late.overlaps(&event(24, 0, 2, "Frühstück")));
+
+        let ics = calendar(vec![late]).to_ics_string();
+        assert!(ics.contains("DTEND;TZID=Europe/Berlin:20231024T010000"));
+    }
+}
@@ -0,0 +1,201 @@
+use std::collections::{HashMap, VecDeque};
+
+use chrono::{NaiveDate, NaiveTime};
+use serde::{Deserialize, Serialize};
+
+use crate::calendar::{Calendar, Event};
+
+/// Changes between two snapshots of the same calendar, see [`Calendar::diff`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CalendarDiff {
+    pub added: Vec<Event>,
+    pub removed: Vec<Event>,
+    /// Pairs of old and new versions of the same event.
+    pub changed: Vec<(Event, Event)>,
+}
+
+impl CalendarDiff {
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+fn diff_key(event: &Event) -> (NaiveDate, NaiveTime, &str) {
+    (event.date, event.start, &event.title)
+}
+
+impl Calendar {
+    /// Events are considered the same if date, start time and title match,
+    /// differences in any other field are reported as a change.
+    #[must_use]
+    pub fn diff(&self, other: &Calendar) -> CalendarDiff {
+        let mut unmatched = HashMap::<_, VecDeque<_>>::new();
+        for (idx, event) in self.events.iter().enumerate() {
+            unmatched.entry(diff_key(event)).or_default().push_back(idx);
+        }
+
+        let mut diff = CalendarDiff::default();
+        let mut matched = vec![false; self.events.len()];
+
+        for new in &other.events {
+            let Some(idx) = unmatched
+                .get_mut(&diff_key(new))
+                .and_then(VecDeque::pop_front)
+            else {
+                diff.added.push(new.clone());
+                continue;
+            };
+
+            matched[idx] = true;
+            let old = &self.events[idx];
+
+            // Freshly parsed events always start at zero, only the other fields tell a change.
+            let mut new = new.clone();
+            new.sequence = old.sequence;
+            if *old != new {
+                new.sequence = old.sequence.saturating_add(1);
+                diff.changed.push((old.clone(), new));
+            }
+        }
+
+        diff.removed = self
+            .events
+            .iter()
+            .zip(matched)
+            .filter(|(_, matched)| !matched)
+            .map(|(event, _)| event.clone())
+            .collect();
+
+        diff
+    }
+
+    /// Applies a diff of this calendar, so that `old.patch(&old.diff(&new))` has the events of `new`.
+    /// Changed events are replaced in place, added events are appended.
+    #[must_use]
+    pub fn patch(&self, diff: &CalendarDiff) -> Calendar {
+        let mut patched = self.clone();
+        for event in &diff.removed {
+            patched.remove_event(event);
+        }
+
+        for (old, new) in &diff.changed {
+            match patched.events.iter_mut().find(|event| *event == old) {
+                Some(event) => *event = new.clone(),
+                None => patched.events.push(new.clone()),
+            }
+        }
+
+        patched.events.extend(diff.added.iter().cloned());
+        patched
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, NaiveTime};
+
+    use super::CalendarDiff;
+    use crate::calendar::{Calendar, Event};
+
+    fn event(day: u32, title: &str, location: &str) -> Event {
+        Event {
+            date: NaiveDate::from_ymd_opt(2023, 10, day).unwrap(),
+            start: NaiveTime::from_hms_opt(8, 0, 0).unwrap(),
+            end: NaiveTime::from_hms_opt(10, 0, 0).unwrap(),
+            end_date: NaiveDate::from_ymd_opt(2023, 10, day).unwrap(),
+            title: title.to_string(),
+            locations: vec![location.to_string()],
+            organizer: None,
+            description: None,
+            notes: None,
+            category: None,
+            rapla_url: None,
+            sequence: 0,
+        }
+    }
+
+    #[test]
+    fn test_diff() {
+        let old = Calendar {
+            name: "TINF22B".to_string(),
+            events: vec![event(23, "A", "HS 101"), event(24, "B", "HS 101")],
+        };
+        let new = Calendar {
+            name: "TINF22B".to_string(),
+            events: vec![event(23, "A", "HS 102"), event(25, "C", "HS 101")],
+        };
+
+        let diff = old.diff(&new);
+        assert_eq!(diff.added, [event(25, "C", "HS 101")]);
+        assert_eq!(diff.removed, [event(24, "B", "HS 101")]);
+
+        let mut changed = event(23, "A", "HS 102");
+        changed.sequence = 1;
+        assert_eq!(diff.changed, [(event(23, "A", "HS 101"), changed)]);
+        assert!(old.diff(&old).is_empty());
+    }
+
+    #[test]
+    fn test_diff_ignores_sequence() {
+        let mut old = event(23, "A", "HS 101");
+        old.sequence = 3;
+        let old = Calendar {
+            name: "TINF22B".to_string(),
+            events: vec![old],
+        };
+        let unchanged = Calendar {
+            name: "TINF22B".to_string(),
+            events: vec![event(23, "A", "HS 101")],
+        };
+        let moved = Calendar {
+            name: "TINF22B".to_string(),
+            events: vec![event(23, "A", "HS 102")],
+        };
+
+        assert!(old.diff(&unchanged).is_empty());
+        assert_eq!(old.patch(&old.diff(&moved)).events[0].sequence, 4);
+    }
+
+    #[test]
+    fn test_patch() {
+        let old = Calendar {
+            name: "TINF22B".to_string(),
+            events: vec![
+                event(23, "A", "HS 101"),
+                event(24, "B", "HS 101"),
+                event(26, "D", "HS 101"),
+            ],
+        };
+        let mut new = Calendar {
+            name: "TINF22B".to_string(),
+            events: vec![
+                event(23, "A", "HS 102"),
+                event(26, "D", "HS 101"),
+                event(25, "C", "HS 101"),
+            ],
+        };
+
+        let patched = old.patch(&old.diff(&new));
+        new.events[0].sequence = 1;
+        assert_eq!(patched, new);
+        assert_eq!(old.patch(&old.diff(&old)), old);
+    }
+
+    #[test]
+    fn test_diff_serde_round_trip() {
+        let old = Calendar {
+            name: "TINF22B".to_string(),
+            events: vec![event(23, "A", "HS 101"), event(24, "B", "HS 101")],
+        };
+        let new = Calendar {
+            name: "TINF22B".to_string(),
+            events: vec![event(23, "A", "HS 102"), event(25, "C", "HS 101")],
+        };
+
+        let diff = old.diff(&new);
+        let json = serde_json::to_string(&diff).unwrap();
+        assert!(json.starts_with(r#"{"added":[{"#));
+        assert_eq!(serde_json::from_str::<CalendarDiff>(&json).unwrap(), diff);
+    }
+}
@@ -0,0 +1,78 @@
+use encoding_rs::{Encoding, UTF_8};
+
+use crate::calendar::Calendar;
+use crate::parser::ParseError;
+
+/// Only the start of the document is searched for a `<meta>` tag, like browsers do.
+const SNIFF_LEN: usize = 1024;
+
+/// The value of the first `charset=` parameter, as found in `Content-Type` headers and `<meta>` tags.
+fn charset_label(s: &str) -> Option<&str> {
+    let (_, rest) = s.split_once("charset=")?;
+    let label = rest
+        .trim_start_matches(['"', '\''])
+        .split(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | ':' | '.')))
+        .next()?;
+    (!label.is_empty()).then_some(label)
+}
+
+fn detect_encoding(bytes: &[u8], content_type: Option<&str>) -> &'static Encoding {
+    let head = String::from_utf8_lossy(&bytes[..bytes.len().min(SNIFF_LEN)]).to_ascii_lowercase();
+    content_type
+        .map(str::to_ascii_lowercase)
+        .as_deref()
+        .and_then(charset_label)
+        .or_else(|| charset_label(&head))
+        .and_then(|label| Encoding::for_label(label.as_bytes()))
+        .unwrap_or(UTF_8)
+}
+
+impl Calendar {
+    /// Like [`Calendar::try_from`], but decodes pages that aren't UTF-8, such as the latin-1 pages of older
+    /// Rapla instances. The charset is taken from the `<meta>` tags of the page and defaults to UTF-8.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ParseError> {
+        Self::from_bytes_with_content_type(bytes, None)
+    }
+
+    /// A charset in the `Content-Type` header takes precedence over the one in the page.
+    pub fn from_bytes_with_content_type(
+        bytes: &[u8],
+        content_type: Option<&str>,
+    ) -> Result<Self, ParseError> {
+        let (html, _, _) = detect_encoding(bytes, content_type).decode(bytes);
+        Self::try_from(html.as_ref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::calendar::Calendar;
+
+    const CALENDAR: &[u8] = b"<html>
+<head><meta http-equiv=\"Content-Type\" content=\"text/html; charset=ISO-8859-1\"><title>TINF22B</title></head>
+<body><form><select name=\"year\"><option selected>2023</option></select></form>
+<div class=\"calendar\"><table class=\"week_table\"><tbody>
+<tr><th class=\"week_number\">KW 43</th><td class=\"week_header\"><nobr>Mo 23.10.</nobr></td></tr>
+<tr><td class=\"week_block\"><a>08:00&nbsp;-10:00<br>Pr\xfcfung<br><span class=\"person\">M\xfcller</span></a></td></tr>
+</tbody></table></div></body>
+</html>";
+
+    #[test]
+    fn test_from_bytes() {
+        let calendar = Calendar::from_bytes(CALENDAR).unwrap();
+        assert_eq!(calendar.events[0].title, "Prüfung");
+        assert_eq!(calendar.events[0].organizer.as_deref(), Some("Müller"));
+        assert!(Calendar::try_from(CALENDAR).is_err());
+    }
+
+    #[test]
+    fn test_from_bytes_with_content_type() {
+        let utf8 = String::from_utf8_lossy(CALENDAR).replace('\u{fffd}', "ü");
+        let calendar = Calendar::from_bytes_with_content_type(
+            utf8.as_bytes(),
+            Some("text/html; charset=UTF-8"),
+        )
+        .unwrap();
+        assert_eq!(calendar.events[0].title, "Prüfung");
+    }
+}
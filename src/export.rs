@@ -0,0 +1,712 @@
+use std::collections::BTreeSet;
+use std::io::{self, Write};
+
+use chrono::{Datelike, Duration, NaiveDate, NaiveTime, Weekday};
+use chrono_tz::Tz;
+use html_escape::encode_text;
+use serde::{Deserialize, Serialize};
+
+use crate::calendar::{deserialize_naive_time, serialize_naive_time, Calendar, Event, ICSOptions};
+#[cfg(feature = "json-ld")]
+use crate::timezone::localize;
+
+/// Error of the JSON conversions, re-exported so callers don't need to depend on `serde_json` themselves.
+pub use serde_json::Error as JsonError;
+
+/// [`Calendar`] with camelCase field names as is common in JSON APIs, e.g. `endDate` instead of `end_date`.
+/// See [`Calendar::to_json_camel_case`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CalendarJson {
+    pub name: String,
+    pub events: Vec<EventJson>,
+}
+
+/// [`Event`] with camelCase field names, see [`CalendarJson`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventJson {
+    pub date: NaiveDate,
+    #[serde(
+        serialize_with = "serialize_naive_time",
+        deserialize_with = "deserialize_naive_time"
+    )]
+    pub start: NaiveTime,
+    #[serde(
+        serialize_with = "serialize_naive_time",
+        deserialize_with = "deserialize_naive_time"
+    )]
+    pub end: NaiveTime,
+    pub end_date: NaiveDate,
+    pub title: String,
+    pub locations: Vec<String>,
+    pub organizer: Option<String>,
+    pub description: Option<String>,
+    pub notes: Option<String>,
+    pub category: Option<String>,
+    pub rapla_url: Option<String>,
+    #[serde(default)]
+    pub sequence: u32,
+}
+
+impl From<Event> for EventJson {
+    fn from(event: Event) -> Self {
+        Self {
+            date: event.date,
+            start: event.start,
+            end: event.end,
+            end_date: event.end_date,
+            title: event.title,
+            locations: event.locations,
+            organizer: event.organizer,
+            description: event.description,
+            notes: event.notes,
+            category: event.category,
+            rapla_url: event.rapla_url,
+            sequence: event.sequence,
+        }
+    }
+}
+
+impl From<EventJson> for Event {
+    fn from(event: EventJson) -> Self {
+        Self {
+            date: event.date,
+            start: event.start,
+            end: event.end,
+            end_date: event.end_date,
+            title: event.title,
+            locations: event.locations,
+            organizer: event.organizer,
+            description: event.description,
+            notes: event.notes,
+            category: event.category,
+            rapla_url: event.rapla_url,
+            sequence: event.sequence,
+        }
+    }
+}
+
+impl From<Calendar> for CalendarJson {
+    fn from(calendar: Calendar) -> Self {
+        Self {
+            name: calendar.name,
+            events: calendar.events.into_iter().map(EventJson::from).collect(),
+        }
+    }
+}
+
+impl From<CalendarJson> for Calendar {
+    fn from(calendar: CalendarJson) -> Self {
+        Self {
+            name: calendar.name,
+            events: calendar.events.into_iter().map(Event::from).collect(),
+        }
+    }
+}
+
+const GOOGLE_EVENT_URL: &str = "https://calendar.google.com/calendar/r/eventedit";
+
+#[derive(Debug, Clone)]
+pub struct PlainTextOptions {
+    /// Lines longer than this many characters are cut off.
+    pub max_width: usize,
+}
+
+impl Default for PlainTextOptions {
+    fn default() -> Self {
+        Self { max_width: 80 }
+    }
+}
+
+fn format_duration(duration: Duration) -> String {
+    format!(
+        "{:02}:{:02}",
+        duration.num_hours(),
+        duration.num_minutes() % 60
+    )
+}
+
+/// Quotes a field as described by RFC 4180, if it needs quoting at all.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+const HTML_TABLE_CSS: &str = "\
+body { font-family: sans-serif; }
+table { border-collapse: collapse; margin-bottom: 2em; }
+th, td { border: 1px solid #ccc; padding: 0.4em; vertical-align: top; }
+td { min-width: 8em; }
+.event { background-color: #e8eef7; border-radius: 4px; padding: 0.2em 0.4em; }
+.event + .event { margin-top: 0.2em; }
+";
+
+#[derive(Debug, Clone, Default)]
+pub struct HtmlTableOptions {
+    /// Page title, the calendar name if not set.
+    pub title: Option<String>,
+    /// Additional CSS appended to the default stylesheet.
+    pub css: Option<String>,
+}
+
+/// Events of one week arranged by time slot and weekday.
+struct WeekGrid<'a> {
+    week: u32,
+    monday: NaiveDate,
+    /// Number of day columns, five unless there are events on the weekend.
+    days: u32,
+    rows: Vec<WeekRow<'a>>,
+}
+
+struct WeekRow<'a> {
+    start: NaiveTime,
+    end: NaiveTime,
+    /// Events per day column.
+    cells: Vec<Vec<&'a Event>>,
+}
+
+impl WeekGrid<'_> {
+    fn day_names(&self) -> impl Iterator<Item = String> + '_ {
+        (0..self.days).map(|day| {
+            (self.monday + Duration::days(day.into()))
+                .format("%A")
+                .to_string()
+        })
+    }
+}
+
+fn week_grids(calendar: &Calendar) -> Vec<WeekGrid<'_>> {
+    let mut grids = Vec::new();
+    for ((year, week), events) in calendar.group_by_week() {
+        let monday =
+            NaiveDate::from_isoywd_opt(year, week, Weekday::Mon).expect("week should be valid");
+        let days = events
+            .iter()
+            .map(|event| event.date.weekday().num_days_from_monday())
+            .max()
+            .map_or(5, |last| (last + 1).max(5));
+        let slots: BTreeSet<_> = events
+            .iter()
+            .map(|event| (event.start, event.end))
+            .collect();
+
+        let rows = slots
+            .into_iter()
+            .map(|(start, end)| {
+                let cells = (0..days)
+                    .map(|day| {
+                        events
+                            .iter()
+                            .copied()
+                            .filter(|event| {
+                                event.date.weekday().num_days_from_monday() == day
+                                    && (event.start, event.end) == (start, end)
+                            })
+                            .collect()
+                    })
+                    .collect();
+                WeekRow { start, end, cells }
+            })
+            .collect();
+
+        grids.push(WeekGrid {
+            week,
+            monday,
+            days,
+            rows,
+        });
+    }
+    grids
+}
+
+fn cell_text(event: &Event) -> String {
+    if event.locations.is_empty() {
+        event.title.clone()
+    } else {
+        format!("{} ({})", event.title, event.locations.join(", "))
+    }
+}
+
+/// A light background color derived from the category name, so the same category always gets the same color.
+fn category_color(category: &str) -> String {
+    let hue = category.bytes().fold(0u32, |hash, byte| {
+        hash.wrapping_mul(31).wrapping_add(byte.into())
+    }) % 360;
+    format!("hsl({hue}, 70%, 85%)")
+}
+
+/// Pipes would end the table cell and line breaks the whole row.
+fn markdown_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace(['\r', '\n'], " ")
+}
+
+impl Calendar {
+    #[must_use]
+    pub fn to_plain_text(&self) -> String {
+        self.to_plain_text_with_options(&PlainTextOptions::default())
+    }
+
+    /// Weekly schedule with one line per event, columns are aligned across the whole calendar.
+    #[must_use]
+    pub fn to_plain_text_with_options(&self, opts: &PlainTextOptions) -> String {
+        let title_width = self
+            .events
+            .iter()
+            .map(|event| event.title.chars().count())
+            .max()
+            .unwrap_or_default();
+
+        let mut lines = Vec::new();
+        for ((year, week), events) in self.group_by_week() {
+            let monday =
+                NaiveDate::from_isoywd_opt(year, week, Weekday::Mon).expect("week should be valid");
+            let last_day = events
+                .iter()
+                .map(|event| event.date)
+                .chain(NaiveDate::from_isoywd_opt(year, week, Weekday::Fri))
+                .max()
+                .expect("friday should be valid");
+
+            if !lines.is_empty() {
+                lines.push(String::new());
+            }
+            lines.push(format!(
+                "\u{2500}\u{2500} Week {week} ({} \u{2013} {}) \u{2500}\u{2500}",
+                monday.format("%b %d"),
+                last_day.format("%b %d")
+            ));
+
+            let mut events = events;
+            events.sort_by_key(|event| (event.date, event.start));
+
+            let mut previous_date = None;
+            for event in events {
+                let weekday = if previous_date == Some(event.date) {
+                    String::new()
+                } else {
+                    event.date.format("%a").to_string()
+                };
+                previous_date = Some(event.date);
+
+                let line = format!(
+                    "{weekday:<3}  {}  {:<title_width$}  {}",
+                    event.start.format("%H:%M"),
+                    event.title,
+                    event.locations.join(", ")
+                );
+                lines.push(line.trim_end().chars().take(opts.max_width).collect());
+            }
+        }
+
+        lines.join("\n")
+    }
+
+    /// One GitHub-Flavored Markdown table per week, with a row per time slot and a column per day.
+    /// Saturdays and sundays only get a column in weeks that have events on them.
+    #[must_use]
+    pub fn to_markdown_table(&self) -> String {
+        let mut tables = Vec::new();
+        for grid in week_grids(self) {
+            let mut lines = vec![
+                format!(
+                    "### Week {} ({})",
+                    grid.week,
+                    grid.monday.format("%Y-%m-%d")
+                ),
+                String::new(),
+            ];
+
+            let mut header = vec!["Time".to_string()];
+            header.extend(grid.day_names());
+            lines.push(format!("| {} |", header.join(" | ")));
+            lines.push(format!("|{}", "---|".repeat(header.len())));
+
+            for row in &grid.rows {
+                let mut cells = vec![format!(
+                    "{}\u{2013}{}",
+                    row.start.format("%H:%M"),
+                    row.end.format("%H:%M")
+                )];
+                cells.extend(row.cells.iter().map(|events| {
+                    if events.is_empty() {
+                        "\u{2014}".to_string()
+                    } else {
+                        events
+                            .iter()
+                            .map(|event| markdown_cell(&cell_text(event)))
+                            .collect::<Vec<_>>()
+                            .join("<br>")
+                    }
+                }));
+                lines.push(format!("| {} |", cells.join(" | ")));
+            }
+
+            tables.push(lines.join("\n"));
+        }
+
+        tables.join("\n\n")
+    }
+
+    #[must_use]
+    pub fn to_html_table(&self) -> String {
+        self.to_html_table_with_options(&HtmlTableOptions::default())
+    }
+
+    /// Self-contained HTML page with one table per week, laid out like [`Calendar::to_markdown_table`].
+    /// Events are colored by their category.
+    #[must_use]
+    pub fn to_html_table_with_options(&self, opts: &HtmlTableOptions) -> String {
+        let title = encode_text(opts.title.as_deref().unwrap_or(&self.name));
+
+        let mut html = String::from("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+        html += &format!("<title>{title}</title>\n<style>\n{HTML_TABLE_CSS}");
+        if let Some(css) = &opts.css {
+            html += css;
+            html.push('\n');
+        }
+        html += &format!("</style>\n</head>\n<body>\n<h1>{title}</h1>\n");
+
+        for grid in week_grids(self) {
+            html += &format!(
+                "<h2>Week {} ({})</h2>\n<table>\n<tr><th>Time</th>",
+                grid.week,
+                grid.monday.format("%Y-%m-%d")
+            );
+            for day in grid.day_names() {
+                html += &format!("<th>{day}</th>");
+            }
+            html += "</tr>\n";
+
+            for row in &grid.rows {
+                html += &format!(
+                    "<tr><th>{}\u{2013}{}</th>",
+                    row.start.format("%H:%M"),
+                    row.end.format("%H:%M")
+                );
+                for events in &row.cells {
+                    html += "<td>";
+                    for event in events {
+                        match &event.category {
+                            Some(category) => {
+                                html += &format!(
+                                    "<div class=\"event\" style=\"background-color: {}\">",
+                                    category_color(category)
+                                );
+                            }
+                            None => html += "<div class=\"event\">",
+                        }
+                        html += &encode_text(&cell_text(event));
+                        html += "</div>";
+                    }
+                    html += "</td>";
+                }
+                html += "</tr>\n";
+            }
+
+            html += "</table>\n";
+        }
+
+        html += "</body>\n</html>\n";
+        html
+    }
+
+    /// Same as [`Calendar::to_json_compact`].
+    pub fn to_json(&self) -> Result<String, JsonError> {
+        self.to_json_compact()
+    }
+
+    pub fn to_json_compact(&self) -> Result<String, JsonError> {
+        serde_json::to_string(self)
+    }
+
+    /// Indented over multiple lines, for debugging or files edited by hand.
+    pub fn to_json_pretty(&self) -> Result<String, JsonError> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Reads back a calendar written by [`Calendar::to_json`] or [`Calendar::to_json_pretty`].
+    pub fn from_json(s: &str) -> Result<Calendar, JsonError> {
+        serde_json::from_str(s)
+    }
+
+    /// Like [`Calendar::to_json_compact`], with the field names of [`CalendarJson`].
+    pub fn to_json_camel_case(&self) -> Result<String, JsonError> {
+        serde_json::to_string(&CalendarJson::from(self.clone()))
+    }
+
+    /// Reads back a calendar written by [`Calendar::to_json_camel_case`].
+    pub fn from_json_camel_case(s: &str) -> Result<Calendar, JsonError> {
+        serde_json::from_str::<CalendarJson>(s).map(Calendar::from)
+    }
+
+    /// Array of schema.org `Event` objects for embedding as structured data into websites.
+    #[cfg(feature = "json-ld")]
+    #[must_use]
+    pub fn to_json_ld(&self) -> String {
+        let tz = ICSOptions::default().timezone;
+        let events: Vec<_> = self
+            .events
+            .iter()
+            .map(|event| {
+                let mut object = serde_json::json!({
+                    "@context": "https://schema.org",
+                    "@type": "Event",
+                    "name": event.title,
+                    "startDate": localize(event.start_datetime(), tz).to_rfc3339(),
+                    "endDate": localize(event.end_datetime(), tz).to_rfc3339(),
+                });
+                if !event.locations.is_empty() {
+                    object["location"] = serde_json::json!({
+                        "@type": "Place",
+                        "name": event.locations.join(", "),
+                    });
+                }
+                if let Some(description) = &event.description {
+                    object["description"] = description.as_str().into();
+                }
+                object
+            })
+            .collect();
+
+        serde_json::Value::from(events).to_string()
+    }
+
+    #[must_use]
+    pub fn to_csv(&self) -> String {
+        let mut buf = Vec::new();
+        self.write_csv(&mut buf)
+            .expect("writing to a vec should not fail");
+        String::from_utf8(buf).expect("csv output should be valid utf-8")
+    }
+
+    pub fn write_csv<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        write!(writer, "Date,Start,End,Title,Location,Duration\r\n")?;
+        for event in &self.events {
+            write!(
+                writer,
+                "{},{},{},{},{},{}\r\n",
+                event.date.format("%Y-%m-%d"),
+                event.start.format("%H:%M"),
+                event.end.format("%H:%M"),
+                csv_field(&event.title),
+                csv_field(&event.locations.join(", ")),
+                format_duration(event.duration()),
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Same output as [`Calendar::write_csv`], with quoting left to the `csv` crate.
+    #[cfg(feature = "csv")]
+    pub fn to_csv_writer<W: Write>(&self, writer: W) -> Result<(), csv::Error> {
+        let mut writer = csv::WriterBuilder::new()
+            .terminator(csv::Terminator::CRLF)
+            .from_writer(writer);
+        writer.write_record(["Date", "Start", "End", "Title", "Location", "Duration"])?;
+        for event in &self.events {
+            writer.write_record([
+                event.date.format("%Y-%m-%d").to_string(),
+                event.start.format("%H:%M").to_string(),
+                event.end.format("%H:%M").to_string(),
+                event.title.clone(),
+                event.locations.join(", "),
+                format_duration(event.duration()),
+            ])?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// One Google Calendar link per event, see [`Event::to_google_url`].
+    #[must_use]
+    pub fn export_to_google_urls(&self) -> Vec<String> {
+        self.events.iter().map(Event::to_google_url).collect()
+    }
+}
+
+impl Event {
+    /// Link that opens Google Calendar with a new event prefilled from this one.
+    #[must_use]
+    pub fn to_google_url(&self) -> String {
+        self.to_google_url_with_timezone(ICSOptions::default().timezone)
+    }
+
+    /// Times are passed as local times of the given timezone.
+    #[must_use]
+    pub fn to_google_url_with_timezone(&self, tz: Tz) -> String {
+        let dates = format!(
+            "{}/{}",
+            self.start_datetime().format("%Y%m%dT%H%M%S"),
+            self.end_datetime().format("%Y%m%dT%H%M%S")
+        );
+
+        let mut query = vec![
+            ("text", self.title.clone()),
+            ("dates", dates),
+            ("ctz", tz.name().to_string()),
+        ];
+        if !self.locations.is_empty() {
+            query.push(("location", self.locations.join(", ")));
+        }
+        if let Some(description) = &self.description {
+            query.push(("details", description.clone()));
+        }
+
+        let query = serde_urlencoded::to_string(query).expect("string pairs should always encode");
+        format!("{GOOGLE_EVENT_URL}?{query}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, NaiveTime};
+
+    use super::HtmlTableOptions;
+    use crate::calendar::{Calendar, Event};
+
+    fn calendar() -> Calendar {
+        Calendar {
+            name: "TINF22B".to_string(),
+            events: vec![Event {
+                date: NaiveDate::from_ymd_opt(2023, 10, 23).unwrap(),
+                start: NaiveTime::from_hms_opt(8, 30, 0).unwrap(),
+                end: NaiveTime::from_hms_opt(10, 0, 0).unwrap(),
+                end_date: NaiveDate::from_ymd_opt(2023, 10, 23).unwrap(),
+                title: "Mathematik \"1\", Analysis".to_string(),
+                locations: vec!["HS 101".to_string()],
+                organizer: None,
+                description: None,
+                notes: None,
+                category: None,
+                rapla_url: None,
+                sequence: 0,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_to_plain_text() {
+        assert_eq!(
+            calendar().to_plain_text(),
+            concat!(
+                "\u{2500}\u{2500} Week 43 (Oct 23 \u{2013} Oct 27) \u{2500}\u{2500}\n",
+                "Mon  08:30  Mathematik \"1\", Analysis  HS 101",
+            )
+        );
+    }
+
+    #[test]
+    fn test_to_markdown_table() {
+        let mut calendar = calendar();
+        let mut other = calendar.events[0].clone();
+        other.date = NaiveDate::from_ymd_opt(2023, 10, 25).unwrap();
+        other.title = "Labor | Gruppe A".to_string();
+        other.locations.clear();
+        calendar.events.push(other);
+
+        assert_eq!(
+            calendar.to_markdown_table(),
+            concat!(
+                "### Week 43 (2023-10-23)\n",
+                "\n",
+                "| Time | Monday | Tuesday | Wednesday | Thursday | Friday |\n",
+                "|---|---|---|---|---|---|\n",
+                "| 08:30\u{2013}10:00 | Mathematik \"1\", Analysis (HS 101) | \u{2014} | Labor \\| Gruppe A | \u{2014} | \u{2014} |",
+            )
+        );
+    }
+
+    #[test]
+    fn test_to_html_table() {
+        let mut calendar = calendar();
+        calendar.events[0].category = Some("category_02".to_string());
+        calendar.events[0].title = "<Mathematik>".to_string();
+
+        let html = calendar.to_html_table_with_options(&HtmlTableOptions {
+            title: None,
+            css: Some("td { color: red; }".to_string()),
+        });
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<title>TINF22B</title>"));
+        assert!(html.contains("td { color: red; }\n</style>"));
+        assert!(html.contains("<tr><th>Time</th><th>Monday</th><th>Tuesday</th><th>Wednesday</th><th>Thursday</th><th>Friday</th></tr>"));
+        assert!(html.contains("style=\"background-color: hsl("));
+        assert!(html.contains("&lt;Mathematik&gt; (HS 101)</div></td><td></td>"));
+    }
+
+    #[test]
+    fn test_to_json() {
+        let calendar = calendar();
+        let compact = calendar.to_json_compact().unwrap();
+        let pretty = calendar.to_json_pretty().unwrap();
+
+        assert_eq!(calendar.to_json().unwrap(), compact);
+        assert!(!compact.contains('\n'));
+        assert!(pretty.starts_with("{\n  \"name\": \"TINF22B\","));
+        assert_eq!(Calendar::from_json(&pretty).unwrap(), calendar);
+    }
+
+    #[test]
+    fn test_to_json_camel_case() {
+        let mut calendar = calendar();
+        calendar.events[0].rapla_url = Some("https://rapla.dhbw.de/rapla/event?id=1".to_string());
+
+        let json = calendar.to_json_camel_case().unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["events"][0]["endDate"], "2023-10-23");
+        assert_eq!(
+            value["events"][0]["raplaUrl"],
+            "https://rapla.dhbw.de/rapla/event?id=1"
+        );
+        assert!(!json.contains("end_date") && !json.contains("rapla_url"));
+        assert_eq!(Calendar::from_json_camel_case(&json).unwrap(), calendar);
+    }
+
+    #[test]
+    fn test_to_csv() {
+        assert_eq!(
+            calendar().to_csv(),
+            concat!(
+                "Date,Start,End,Title,Location,Duration\r\n",
+                "2023-10-23,08:30,10:00,\"Mathematik \"\"1\"\", Analysis\",HS 101,01:30\r\n",
+            )
+        );
+    }
+
+    #[cfg(feature = "json-ld")]
+    #[test]
+    fn test_to_json_ld() {
+        let json_ld: serde_json::Value = serde_json::from_str(&calendar().to_json_ld()).unwrap();
+        assert_eq!(
+            json_ld,
+            serde_json::json!([{
+                "@context": "https://schema.org",
+                "@type": "Event",
+                "name": "Mathematik \"1\", Analysis",
+                "startDate": "2023-10-23T08:30:00+02:00",
+                "endDate": "2023-10-23T10:00:00+02:00",
+                "location": { "@type": "Place", "name": "HS 101" },
+            }])
+        );
+    }
+
+    #[cfg(feature = "csv")]
+    #[test]
+    fn test_to_csv_writer() {
+        let mut buf = Vec::new();
+        calendar().to_csv_writer(&mut buf).unwrap();
+        assert_eq!(String::from_utf8(buf).unwrap(), calendar().to_csv());
+    }
+
+    #[test]
+    fn test_to_google_url() {
+        assert_eq!(
+            calendar().export_to_google_urls(),
+            ["https://calendar.google.com/calendar/r/eventedit?text=Mathematik+%221%22%2C+Analysis&dates=20231023T083000%2F20231023T100000&ctz=Europe%2FBerlin&location=HS+101"]
+        );
+    }
+}
@@ -0,0 +1,125 @@
+#[cfg(feature = "atom")]
+use atom_syndication::{Entry, Feed, Text};
+#[cfg(feature = "atom")]
+use chrono::Utc;
+use chrono::{DateTime, FixedOffset};
+#[cfg(feature = "rss")]
+use rss::{Channel, Guid, Item};
+
+use crate::calendar::{Calendar, Event, ICSOptions};
+use crate::timezone::localize;
+
+/// Time and location of an event, e.g. "08:00–10:00, HS 101".
+fn summary(event: &Event) -> String {
+    let mut summary = format!(
+        "{}\u{2013}{}",
+        event.start.format("%H:%M"),
+        event.end.format("%H:%M")
+    );
+    if !event.locations.is_empty() {
+        summary += ", ";
+        summary += &event.locations.join(", ");
+    }
+    summary
+}
+
+fn published(event: &Event) -> DateTime<FixedOffset> {
+    localize(event.start_datetime(), ICSOptions::default().timezone).fixed_offset()
+}
+
+impl Calendar {
+    /// RSS 2.0 feed with one item per event, published at the start of the event.
+    #[cfg(feature = "rss")]
+    #[must_use]
+    pub fn to_rss(&self) -> String {
+        let items: Vec<_> = self
+            .events
+            .iter()
+            .map(|event| {
+                let mut item = Item::default();
+                item.set_title(event.title.clone());
+                item.set_description(summary(event));
+                item.set_pub_date(published(event).to_rfc2822());
+                item.set_guid(Guid {
+                    value: event.uid(),
+                    permalink: false,
+                });
+                item
+            })
+            .collect();
+
+        let mut channel = Channel::default();
+        channel.set_title(self.name.clone());
+        channel.set_description(format!("Schedule of {}", self.name));
+        channel.set_items(items);
+        channel.to_string()
+    }
+
+    /// Atom feed with one entry per event, published at the start of the event.
+    #[cfg(feature = "atom")]
+    #[must_use]
+    pub fn to_atom(&self) -> String {
+        let entries: Vec<_> = self
+            .events
+            .iter()
+            .map(|event| {
+                let mut entry = Entry::default();
+                entry.set_title(event.title.clone());
+                entry.set_id(format!("urn:rapla-ical-proxy:{}", event.uid()));
+                entry.set_summary(Text::plain(summary(event)));
+                entry.set_published(published(event));
+                entry.set_updated(published(event));
+                entry
+            })
+            .collect();
+
+        let mut feed = Feed::default();
+        feed.set_title(self.name.clone());
+        feed.set_id(format!("urn:rapla-ical-proxy:{}", self.name));
+        feed.set_updated(Utc::now().fixed_offset());
+        feed.set_entries(entries);
+        feed.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, NaiveTime};
+
+    use crate::builder::{CalendarBuilder, EventBuilder};
+    use crate::calendar::Calendar;
+
+    fn calendar() -> Calendar {
+        CalendarBuilder::new("TINF22B")
+            .add_event(
+                EventBuilder::new(
+                    NaiveDate::from_ymd_opt(2023, 10, 23).unwrap(),
+                    NaiveTime::from_hms_opt(8, 0, 0).unwrap(),
+                    NaiveTime::from_hms_opt(10, 0, 0).unwrap(),
+                    "Mathematik",
+                )
+                .location("HS 101")
+                .build(),
+            )
+            .build()
+            .unwrap()
+    }
+
+    #[cfg(feature = "rss")]
+    #[test]
+    fn test_to_rss() {
+        let rss = calendar().to_rss();
+        assert!(rss.contains("<title>Mathematik</title>"));
+        assert!(rss.contains("<description><![CDATA[08:00\u{2013}10:00, HS 101]]></description>"));
+        assert!(rss.contains("<pubDate>Mon, 23 Oct 2023 08:00:00 +0200</pubDate>"));
+    }
+
+    #[cfg(feature = "atom")]
+    #[test]
+    fn test_to_atom() {
+        let atom = calendar().to_atom();
+        assert!(atom.contains("<title>Mathematik</title>"));
+        assert!(atom.contains("<published>2023-10-23T08:00:00+02:00</published>"));
+        assert!(atom.contains("08:00\u{2013}10:00, HS 101"));
+    }
+}
@@ -0,0 +1,508 @@
+#[cfg(feature = "fetch")]
+use std::collections::HashMap;
+use std::fmt;
+use std::time::Duration;
+#[cfg(feature = "fetch")]
+use std::time::Instant;
+
+use chrono::{Datelike, Utc};
+#[cfg(feature = "fetch")]
+use reqwest::header::{self, HeaderValue};
+use reqwest::StatusCode;
+
+use crate::calendar::Calendar;
+#[cfg(feature = "fetch")]
+use crate::diff::CalendarDiff;
+use crate::parser::ParseError;
+use crate::url::RaplaUrl;
+
+const DEFAULT_USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+
+#[derive(Debug)]
+pub enum FetchError {
+    Request(reqwest::Error),
+    Status(StatusCode),
+    Parse(ParseError),
+}
+
+impl fmt::Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Request(err) => write!(f, "request failed: {err}"),
+            Self::Status(status) => write!(f, "upstream returned unexpected status code {status}"),
+            Self::Parse(err) => write!(f, "can't parse calendar: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for FetchError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Request(err) => Some(err),
+            Self::Status(_) => None,
+            Self::Parse(err) => Some(err),
+        }
+    }
+}
+
+impl From<reqwest::Error> for FetchError {
+    fn from(value: reqwest::Error) -> Self {
+        Self::Request(value)
+    }
+}
+
+impl From<ParseError> for FetchError {
+    fn from(value: ParseError) -> Self {
+        Self::Parse(value)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FetchOptions {
+    pub timeout: Option<Duration>,
+    pub user_agent: String,
+    /// Proxy URL that all requests are routed through, e.g. `http://localhost:3128`.
+    pub proxy: Option<String>,
+}
+
+impl Default for FetchOptions {
+    fn default() -> Self {
+        Self {
+            timeout: Some(Duration::from_secs(30)),
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            proxy: None,
+        }
+    }
+}
+
+/// The parser needs to know which year the first page starts in, we take it from the link itself if possible.
+fn start_year(url: &str) -> i32 {
+    RaplaUrl::parse(url)
+        .ok()
+        .and_then(|url| url.year)
+        .unwrap_or_else(|| Utc::now().year())
+}
+
+#[cfg(feature = "fetch")]
+fn blocking_client(opts: &FetchOptions) -> Result<reqwest::blocking::Client, FetchError> {
+    let mut builder = reqwest::blocking::Client::builder().user_agent(&opts.user_agent);
+    if let Some(timeout) = opts.timeout {
+        builder = builder.timeout(timeout);
+    }
+    if let Some(proxy) = &opts.proxy {
+        builder = builder.proxy(reqwest::Proxy::all(proxy)?);
+    }
+
+    Ok(builder.build()?)
+}
+
+#[cfg(feature = "fetch")]
+impl Calendar {
+    pub fn from_url(url: &str) -> Result<Self, FetchError> {
+        Self::from_url_with_options(url, &FetchOptions::default())
+    }
+
+    pub fn from_url_with_options(url: &str, opts: &FetchOptions) -> Result<Self, FetchError> {
+        let response = blocking_client(opts)?.get(url).send()?;
+        if !response.status().is_success() {
+            return Err(FetchError::Status(response.status()));
+        }
+
+        let html = response.text()?;
+        Ok(crate::parser::parse_calendar(&html, start_year(url))?)
+    }
+}
+
+#[cfg(feature = "fetch")]
+#[derive(Debug, Clone)]
+pub enum FetchResult {
+    Fresh(Calendar),
+    /// Upstream reported no changes since the last fetch, see [`CachedFetcher::cached`].
+    Unchanged,
+}
+
+#[cfg(feature = "fetch")]
+#[derive(Debug)]
+struct CacheEntry {
+    etag: Option<HeaderValue>,
+    last_modified: Option<HeaderValue>,
+    calendar: Calendar,
+}
+
+/// Remembers the validators sent by upstream and only downloads calendars again once they changed.
+#[cfg(feature = "fetch")]
+#[derive(Debug)]
+pub struct CachedFetcher {
+    client: reqwest::blocking::Client,
+    entries: HashMap<String, CacheEntry>,
+}
+
+#[cfg(feature = "fetch")]
+impl CachedFetcher {
+    pub fn new() -> Result<Self, FetchError> {
+        Self::with_options(&FetchOptions::default())
+    }
+
+    pub fn with_options(opts: &FetchOptions) -> Result<Self, FetchError> {
+        Ok(Self {
+            client: blocking_client(opts)?,
+            entries: HashMap::new(),
+        })
+    }
+
+    /// The calendar last fetched from the given URL.
+    #[must_use]
+    pub fn cached(&self, url: &str) -> Option<&Calendar> {
+        self.entries.get(url).map(|entry| &entry.calendar)
+    }
+
+    pub fn fetch(&mut self, url: &str) -> Result<FetchResult, FetchError> {
+        let mut request = self.client.get(url);
+        if let Some(entry) = self.entries.get(url) {
+            if let Some(etag) = &entry.etag {
+                request = request.header(header::IF_NONE_MATCH, etag);
+            }
+            if let Some(last_modified) = &entry.last_modified {
+                request = request.header(header::IF_MODIFIED_SINCE, last_modified);
+            }
+        }
+
+        let response = request.send()?;
+        if response.status() == StatusCode::NOT_MODIFIED && self.entries.contains_key(url) {
+            return Ok(FetchResult::Unchanged);
+        }
+        if !response.status().is_success() {
+            return Err(FetchError::Status(response.status()));
+        }
+
+        let etag = response.headers().get(header::ETAG).cloned();
+        let last_modified = response.headers().get(header::LAST_MODIFIED).cloned();
+        let calendar = crate::parser::parse_calendar(&response.text()?, start_year(url))?;
+
+        self.entries.insert(
+            url.to_string(),
+            CacheEntry {
+                etag,
+                last_modified,
+                calendar: calendar.clone(),
+            },
+        );
+
+        Ok(FetchResult::Fresh(calendar))
+    }
+}
+
+/// Keeps a copy of a calendar up to date by polling upstream, see [`CalendarSubscription::poll`].
+#[cfg(feature = "fetch")]
+#[derive(Debug)]
+pub struct CalendarSubscription {
+    url: String,
+    poll_interval: Duration,
+    fetcher: CachedFetcher,
+    current: Calendar,
+    last_poll: Option<Instant>,
+}
+
+#[cfg(feature = "fetch")]
+impl CalendarSubscription {
+    pub fn new(url: String, poll_interval: Duration) -> Result<Self, FetchError> {
+        Ok(Self {
+            url,
+            poll_interval,
+            fetcher: CachedFetcher::new()?,
+            current: Calendar::default(),
+            last_poll: None,
+        })
+    }
+
+    /// The calendar as of the last poll, empty until the first one succeeded.
+    #[must_use]
+    pub fn current(&self) -> &Calendar {
+        &self.current
+    }
+
+    /// Fetches the calendar and returns what changed since the last poll, `None` if nothing did.
+    /// Upstream is only asked again once the poll interval has passed since the last successful
+    /// poll, earlier calls return `None` right away. After a failed poll the next call tries again
+    /// immediately. The first successful poll reports every event as added.
+    pub fn poll(&mut self) -> Result<Option<CalendarDiff>, FetchError> {
+        if self
+            .last_poll
+            .is_some_and(|last_poll| last_poll.elapsed() < self.poll_interval)
+        {
+            return Ok(None);
+        }
+
+        let result = self.fetcher.fetch(&self.url)?;
+        self.last_poll = Some(Instant::now());
+
+        let FetchResult::Fresh(calendar) = result else {
+            return Ok(None);
+        };
+
+        let diff = self.current.diff(&calendar);
+        if diff.is_empty() {
+            return Ok(None);
+        }
+
+        self.current = self.current.patch(&diff);
+        self.current.name = calendar.name;
+        Ok(Some(diff))
+    }
+}
+
+#[cfg(feature = "async-fetch")]
+impl Calendar {
+    pub async fn from_url_async(url: &str) -> Result<Self, FetchError> {
+        Self::from_url_async_with_options(url, &FetchOptions::default()).await
+    }
+
+    pub async fn from_url_async_with_options(
+        url: &str,
+        opts: &FetchOptions,
+    ) -> Result<Self, FetchError> {
+        let mut builder = reqwest::Client::builder();
+        if let Some(proxy) = &opts.proxy {
+            builder = builder.proxy(reqwest::Proxy::all(proxy)?);
+        }
+
+        Self::from_url_with_client(&builder.build()?, url, opts).await
+    }
+
+    /// Reuses an existing client and its connection pool. The proxy option is ignored here,
+    /// proxies can only be configured when building the client.
+    pub async fn from_url_with_client(
+        client: &reqwest::Client,
+        url: &str,
+        opts: &FetchOptions,
+    ) -> Result<Self, FetchError> {
+        let mut request = client
+            .get(url)
+            .header(reqwest::header::USER_AGENT, &opts.user_agent);
+        if let Some(timeout) = opts.timeout {
+            request = request.timeout(timeout);
+        }
+
+        let response = request.send().await?;
+        if !response.status().is_success() {
+            return Err(FetchError::Status(response.status()));
+        }
+
+        let html = response.text().await?;
+        Ok(crate::parser::parse_calendar(&html, start_year(url))?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "fetch")]
+    use std::io::{BufRead, BufReader, Write};
+    #[cfg(feature = "fetch")]
+    use std::net::TcpListener;
+    #[cfg(feature = "fetch")]
+    use std::sync::{Arc, Mutex};
+    #[cfg(feature = "fetch")]
+    use std::thread;
+    #[cfg(feature = "fetch")]
+    use std::time::Duration;
+
+    #[cfg(feature = "fetch")]
+    use super::{CachedFetcher, CalendarSubscription, FetchError, FetchResult};
+    #[cfg(feature = "async-fetch")]
+    use crate::calendar::Calendar;
+
+    #[cfg(feature = "fetch")]
+    const PAGE: &str = r#"<html><head><title>TINF22B</title></head><body>
+<div class="calendar"><table class="week_table"><tbody>
+<tr><th class="week_number">KW 43</th><td class="week_header"><nobr>Mo 23.10.</nobr></td></tr>
+<tr><td class="week_times">08:00</td><td class="week_block"><a>08:00&nbsp;-10:00<br>Mathematik</a></td></tr>
+</tbody></table></div></body></html>"#;
+
+    /// A local stand-in for Rapla, answering 304 once a request carries the current ETag and
+    /// 503 as long as there is no page.
+    #[cfg(feature = "fetch")]
+    struct Upstream {
+        url: String,
+        page: Arc<Mutex<Option<(String, String)>>>,
+        requests: Arc<Mutex<Vec<String>>>,
+    }
+
+    #[cfg(feature = "fetch")]
+    impl Upstream {
+        fn start(etag: &str, body: &str) -> Self {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let url = format!(
+                "http://{}/rapla/calendar?key=abc&year=2023",
+                listener.local_addr().unwrap()
+            );
+            let upstream = Self {
+                url,
+                page: Arc::new(Mutex::new(None)),
+                requests: Arc::new(Mutex::new(Vec::new())),
+            };
+            upstream.update(etag, body);
+
+            let page = Arc::clone(&upstream.page);
+            let requests = Arc::clone(&upstream.requests);
+            thread::spawn(move || {
+                for stream in listener.incoming() {
+                    let mut stream = stream.unwrap();
+                    let mut reader = BufReader::new(stream.try_clone().unwrap());
+                    let mut head = String::new();
+                    loop {
+                        let mut line = String::new();
+                        if reader.read_line(&mut line).unwrap() == 0 || line == "\r\n" {
+                            break;
+                        }
+                        head += &line.to_ascii_lowercase();
+                    }
+
+                    let response = match &*page.lock().unwrap() {
+                        Some((etag, _)) if head.contains(&format!("if-none-match: {etag}\r\n")) => {
+                            "HTTP/1.1 304 Not Modified\r\nConnection: close\r\n\r\n".to_string()
+                        }
+                        Some((etag, body)) => format!(
+                            concat!(
+                                "HTTP/1.1 200 OK\r\nETag: {}\r\n",
+                                "Last-Modified: Mon, 23 Oct 2023 08:00:00 GMT\r\n",
+                                "Content-Type: text/html\r\nContent-Length: {}\r\n",
+                                "Connection: close\r\n\r\n{}"
+                            ),
+                            etag,
+                            body.len(),
+                            body
+                        ),
+                        None => "HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                            .to_string(),
+                    };
+
+                    requests.lock().unwrap().push(head);
+                    stream.write_all(response.as_bytes()).unwrap();
+                }
+            });
+
+            upstream
+        }
+
+        fn update(&self, etag: &str, body: &str) {
+            *self.page.lock().unwrap() = Some((etag.to_string(), body.to_string()));
+        }
+
+        fn fail(&self) {
+            *self.page.lock().unwrap() = None;
+        }
+
+        fn requests(&self) -> Vec<String> {
+            self.requests.lock().unwrap().clone()
+        }
+    }
+
+    #[cfg(feature = "fetch")]
+    #[test]
+    fn test_cached_fetcher() {
+        let upstream = Upstream::start("\"v1\"", PAGE);
+        let mut fetcher = CachedFetcher::new().unwrap();
+
+        let FetchResult::Fresh(calendar) = fetcher.fetch(&upstream.url).unwrap() else {
+            panic!("first fetch should download the calendar");
+        };
+        assert_eq!(calendar.events.len(), 1);
+        assert_eq!(fetcher.cached(&upstream.url), Some(&calendar));
+
+        assert!(matches!(
+            fetcher.fetch(&upstream.url).unwrap(),
+            FetchResult::Unchanged
+        ));
+        let requests = upstream.requests();
+        assert!(!requests[0].contains("if-none-match"));
+        assert!(requests[1].contains("if-none-match: \"v1\"\r\n"));
+        assert!(requests[1].contains("if-modified-since: mon, 23 oct 2023 08:00:00 gmt\r\n"));
+
+        upstream.update("\"v2\"", &PAGE.replace("Mathematik", "Statistik"));
+        let FetchResult::Fresh(calendar) = fetcher.fetch(&upstream.url).unwrap() else {
+            panic!("fetch should download the changed calendar");
+        };
+        assert_eq!(calendar.events[0].title, "Statistik");
+        assert_eq!(fetcher.cached(&upstream.url), Some(&calendar));
+    }
+
+    #[cfg(feature = "fetch")]
+    #[test]
+    fn test_cached_fetcher_status() {
+        let upstream = Upstream::start("\"v1\"", PAGE);
+        upstream.fail();
+
+        let mut fetcher = CachedFetcher::new().unwrap();
+        assert!(matches!(
+            fetcher.fetch(&upstream.url),
+            Err(FetchError::Status(status)) if status.as_u16() == 503
+        ));
+        assert_eq!(fetcher.cached(&upstream.url), None);
+    }
+
+    #[cfg(feature = "fetch")]
+    #[test]
+    fn test_subscription_poll_interval() {
+        let upstream = Upstream::start("\"v1\"", PAGE);
+        let mut subscription =
+            CalendarSubscription::new(upstream.url.clone(), Duration::from_secs(3600)).unwrap();
+        assert!(subscription.current().events.is_empty());
+
+        let diff = subscription.poll().unwrap().unwrap();
+        assert_eq!(diff.added.len(), 1);
+        assert_eq!(subscription.current().name, "TINF22B");
+        assert_eq!(subscription.current().events, diff.added);
+
+        assert!(subscription.poll().unwrap().is_none());
+        assert_eq!(upstream.requests().len(), 1);
+    }
+
+    #[cfg(feature = "fetch")]
+    #[test]
+    fn test_subscription_applies_changes() {
+        let upstream = Upstream::start("\"v1\"", PAGE);
+        let mut subscription =
+            CalendarSubscription::new(upstream.url.clone(), Duration::ZERO).unwrap();
+
+        assert!(subscription.poll().unwrap().is_some());
+        assert!(subscription.poll().unwrap().is_none());
+        assert_eq!(upstream.requests().len(), 2);
+
+        upstream.update(
+            "\"v2\"",
+            &PAGE.replace(
+                "Mathematik</a>",
+                "Mathematik<br><span class=\"resource\">HS 101</span></a>",
+            ),
+        );
+        let diff = subscription.poll().unwrap().unwrap();
+        assert_eq!(diff.changed.len(), 1);
+
+        let event = &subscription.current().events[0];
+        assert_eq!(event.locations, ["HS 101"]);
+        assert_eq!(event.sequence, 1);
+    }
+
+    #[cfg(feature = "fetch")]
+    #[test]
+    fn test_subscription_retries_after_error() {
+        let upstream = Upstream::start("\"v1\"", PAGE);
+        upstream.fail();
+        let mut subscription =
+            CalendarSubscription::new(upstream.url.clone(), Duration::from_secs(3600)).unwrap();
+
+        assert!(subscription.poll().is_err());
+        upstream.update("\"v1\"", PAGE);
+        assert!(subscription.poll().unwrap().is_some());
+        assert_eq!(upstream.requests().len(), 2);
+    }
+
+    #[cfg(feature = "async-fetch")]
+    #[test]
+    fn test_from_url_async_is_send() {
+        fn assert_send<T: Send>(_: &T) {}
+        assert_send(&Calendar::from_url_async(
+            "https://rapla.dhbw.de/rapla/calendar",
+        ));
+    }
+}
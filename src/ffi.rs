@@ -0,0 +1,112 @@
+//! C bindings, the header is generated with `cbindgen --output rapla.h`.
+//! The shared library is built with `cargo rustc --lib --release --no-default-features --features ffi --crate-type cdylib`.
+
+use std::ffi::{c_char, CString};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+use std::slice;
+
+use crate::calendar::Calendar;
+
+/// Opaque handle to a parsed calendar.
+pub struct RaplaCalendar(Calendar);
+
+/// Runs the closure, turning panics into a null pointer instead of unwinding into C.
+fn catch_null<T, F: FnOnce() -> *mut T>(f: F) -> *mut T {
+    panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or(ptr::null_mut())
+}
+
+/// Parses a Rapla page of `html_len` bytes, the start year is taken from the page itself.
+/// Returns null if the page can't be parsed, free the result with [`rapla_calendar_free`].
+///
+/// # Safety
+///
+/// `html` must be null or point to at least `html_len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn rapla_calendar_from_html(
+    html: *const c_char,
+    html_len: usize,
+) -> *mut RaplaCalendar {
+    if html.is_null() {
+        return ptr::null_mut();
+    }
+
+    // SAFETY: The caller guarantees that the buffer is valid for the given length.
+    let html = unsafe { slice::from_raw_parts(html.cast::<u8>(), html_len) };
+    catch_null(|| match Calendar::try_from(html) {
+        Ok(calendar) => Box::into_raw(Box::new(RaplaCalendar(calendar))),
+        Err(_) => ptr::null_mut(),
+    })
+}
+
+/// Returns the calendar as a NUL-terminated ICS string, or null if it contains NUL bytes.
+/// Free the result with [`rapla_string_free`].
+///
+/// # Safety
+///
+/// `cal` must be null or a pointer returned by [`rapla_calendar_from_html`] that wasn't freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn rapla_calendar_to_ics(cal: *const RaplaCalendar) -> *mut c_char {
+    // SAFETY: The caller guarantees that the pointer is null or valid.
+    let Some(RaplaCalendar(calendar)) = (unsafe { cal.as_ref() }) else {
+        return ptr::null_mut();
+    };
+
+    catch_null(|| CString::new(calendar.to_ics_string()).map_or(ptr::null_mut(), CString::into_raw))
+}
+
+/// # Safety
+///
+/// `cal` must be null or a pointer returned by [`rapla_calendar_from_html`] that wasn't freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn rapla_calendar_free(cal: *mut RaplaCalendar) {
+    if !cal.is_null() {
+        // SAFETY: The pointer was created by `Box::into_raw` and is not used afterwards.
+        drop(unsafe { Box::from_raw(cal) });
+    }
+}
+
+/// # Safety
+///
+/// `s` must be null or a string returned by this library that wasn't freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn rapla_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        // SAFETY: The pointer was created by `CString::into_raw` and is not used afterwards.
+        drop(unsafe { CString::from_raw(s) });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CStr;
+    use std::ptr;
+
+    use super::{
+        rapla_calendar_free, rapla_calendar_from_html, rapla_calendar_to_ics, rapla_string_free,
+    };
+
+    #[test]
+    fn test_ffi_round_trip() {
+        let html = r#"<html><head><title>TINF22B</title></head><body>
+<select name="year"><option selected>2023</option></select></body></html>"#;
+
+        unsafe {
+            let cal = rapla_calendar_from_html(html.as_ptr().cast(), html.len());
+            assert!(!cal.is_null());
+
+            let ics = rapla_calendar_to_ics(cal);
+            assert!(CStr::from_ptr(ics)
+                .to_str()
+                .unwrap()
+                .contains("X-WR-CALNAME:TINF22B"));
+
+            rapla_string_free(ics);
+            rapla_calendar_free(cal);
+
+            assert!(rapla_calendar_from_html(ptr::null(), 0).is_null());
+            assert!(rapla_calendar_from_html("<p>".as_ptr().cast(), 3).is_null());
+            assert!(rapla_calendar_to_ics(ptr::null()).is_null());
+        }
+    }
+}
@@ -0,0 +1,304 @@
+use std::fmt;
+
+use chrono::{NaiveDateTime, TimeZone as _};
+use chrono_tz::Tz;
+
+use crate::calendar::{Calendar, Event, ICSOptions, LOCATION_SEPARATOR};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IcsParseError {
+    MissingCalendar,
+    UnterminatedComponent,
+    MalformedLine { raw: String },
+    MalformedDateTime { raw: String },
+    MissingProperty(&'static str),
+}
+
+impl fmt::Display for IcsParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingCalendar => write!(f, "no VCALENDAR component found"),
+            Self::UnterminatedComponent => write!(f, "component is missing its END line"),
+            Self::MalformedLine { raw } => write!(f, "malformed content line: {raw:?}"),
+            Self::MalformedDateTime { raw } => write!(f, "malformed date-time: {raw:?}"),
+            Self::MissingProperty(name) => write!(f, "event is missing the {name} property"),
+        }
+    }
+}
+
+impl std::error::Error for IcsParseError {}
+
+/// Joins folded lines back together, continuation lines start with a space or tab.
+fn unfold(s: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in s.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(continuation), Some(last)) => last.push_str(continuation),
+            _ if line.is_empty() => {}
+            _ => lines.push(line.to_string()),
+        }
+    }
+    lines
+}
+
+/// Splits a content line into its name, parameters and value.
+fn split_line(line: &str) -> Result<(&str, &str, &str), IcsParseError> {
+    // Parameter values may be quoted and contain colons themselves.
+    let mut quoted = false;
+    let colon = line
+        .char_indices()
+        .find(|&(_, c)| {
+            if c == '"' {
+                quoted = !quoted;
+            }
+            c == ':' && !quoted
+        })
+        .map(|(idx, _)| idx)
+        .ok_or_else(|| IcsParseError::MalformedLine {
+            raw: line.to_string(),
+        })?;
+
+    let (head, value) = (&line[..colon], &line[colon + 1..]);
+    let (name, params) = head.split_once(';').unwrap_or((head, ""));
+    Ok((name, params, value))
+}
+
+fn unescape_text(s: &str) -> String {
+    let mut unescaped = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => unescaped.push('\n'),
+            Some(other) => unescaped.push(other),
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
+
+/// Reverses the escaping of separators inside of locations done by [`Calendar::to_ics`].
+fn split_locations(s: &str) -> Vec<String> {
+    let mut locations = Vec::new();
+    let mut location = String::new();
+    let mut rest = s;
+    while let Some(c) = rest.chars().next() {
+        if let Some(after) = rest.strip_prefix(LOCATION_SEPARATOR) {
+            locations.push(std::mem::take(&mut location));
+            rest = after;
+            continue;
+        }
+
+        rest = &rest[c.len_utf8()..];
+        if c == '\\' {
+            if let Some(escaped) = rest.chars().next() {
+                location.push(escaped);
+                rest = &rest[escaped.len_utf8()..];
+                continue;
+            }
+        }
+        location.push(c);
+    }
+    locations.push(location);
+    locations
+}
+
+/// Local times are taken as is, UTC times are converted to the given timezone.
+fn parse_datetime(raw: &str, tz: Tz) -> Result<NaiveDateTime, IcsParseError> {
+    let malformed = || IcsParseError::MalformedDateTime {
+        raw: raw.to_string(),
+    };
+
+    match raw.strip_suffix('Z') {
+        Some(utc) => NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S")
+            .map(|utc| tz.from_utc_datetime(&utc).naive_local())
+            .map_err(|_| malformed()),
+        None => NaiveDateTime::parse_from_str(raw, "%Y%m%dT%H%M%S").map_err(|_| malformed()),
+    }
+}
+
+#[derive(Default)]
+struct PartialEvent {
+    start: Option<NaiveDateTime>,
+    end: Option<NaiveDateTime>,
+    title: Option<String>,
+    locations: Vec<String>,
+    organizer: Option<String>,
+    description: Option<String>,
+    notes: Option<String>,
+    category: Option<String>,
+    rapla_url: Option<String>,
+    sequence: u32,
+}
+
+impl PartialEvent {
+    fn build(self) -> Result<Event, IcsParseError> {
+        let start = self
+            .start
+            .ok_or(IcsParseError::MissingProperty("DTSTART"))?;
+        let end = self.end.ok_or(IcsParseError::MissingProperty("DTEND"))?;
+
+        Ok(Event {
+            date: start.date(),
+            start: start.time(),
+            end: end.time(),
+            end_date: end.date(),
+            title: self
+                .title
+                .ok_or(IcsParseError::MissingProperty("SUMMARY"))?,
+            locations: self.locations,
+            organizer: self.organizer,
+            description: self.description,
+            notes: self.notes,
+            category: self.category,
+            rapla_url: self.rapla_url,
+            sequence: self.sequence,
+        })
+    }
+}
+
+impl Calendar {
+    /// Reads back the subset of RFC 5545 that [`Calendar::to_ics`] produces.
+    pub fn from_ics(s: &str) -> Result<Calendar, IcsParseError> {
+        Self::from_ics_with_timezone(s, ICSOptions::default().timezone)
+    }
+
+    /// UTC times are converted to the given timezone, like the times shown by Rapla.
+    pub fn from_ics_with_timezone(s: &str, tz: Tz) -> Result<Calendar, IcsParseError> {
+        let mut calendar = None;
+        let mut event = None;
+        // Depth of nested components inside of an event, such as alarms.
+        let mut nested = 0;
+
+        for line in unfold(s) {
+            let (name, _, value) = split_line(&line)?;
+            match (name, value) {
+                ("BEGIN", "VCALENDAR") => calendar = Some(Calendar::default()),
+                ("END", "VCALENDAR") => break,
+                ("BEGIN", "VEVENT") => event = Some(PartialEvent::default()),
+                ("END", "VEVENT") => {
+                    let event = event.take().ok_or(IcsParseError::UnterminatedComponent)?;
+                    calendar
+                        .as_mut()
+                        .ok_or(IcsParseError::MissingCalendar)?
+                        .events
+                        .push(event.build()?);
+                }
+                ("BEGIN", _) if event.is_some() => nested += 1,
+                ("END", _) if event.is_some() => nested -= 1,
+                _ if nested > 0 => {}
+                _ => {
+                    if let Some(event) = &mut event {
+                        match name {
+                            "DTSTART" => event.start = Some(parse_datetime(value, tz)?),
+                            "DTEND" => event.end = Some(parse_datetime(value, tz)?),
+                            "SUMMARY" => event.title = Some(unescape_text(value)),
+                            "LOCATION" => {
+                                event.locations = split_locations(&unescape_text(value));
+                            }
+                            "ORGANIZER" => event.organizer = Some(value.to_string()),
+                            "DESCRIPTION" => event.description = Some(unescape_text(value)),
+                            "COMMENT" => event.notes = Some(unescape_text(value)),
+                            "CATEGORIES" => event.category = Some(unescape_text(value)),
+                            "URL" => event.rapla_url = Some(value.to_string()),
+                            "SEQUENCE" => {
+                                event.sequence =
+                                    value.parse().map_err(|_| IcsParseError::MalformedLine {
+                                        raw: line.to_string(),
+                                    })?;
+                            }
+                            _ => {}
+                        }
+                    } else if let Some(calendar) = &mut calendar {
+                        if name == "X-WR-CALNAME" {
+                            calendar.name = unescape_text(value);
+                        }
+                    }
+                }
+            }
+        }
+
+        if event.is_some() {
+            return Err(IcsParseError::UnterminatedComponent);
+        }
+        calendar.ok_or(IcsParseError::MissingCalendar)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, NaiveDate, NaiveTime};
+
+    use super::IcsParseError;
+    use crate::builder::{CalendarBuilder, EventBuilder};
+    use crate::calendar::{Calendar, ICSOptions};
+
+    fn calendar() -> Calendar {
+        let date = NaiveDate::from_ymd_opt(2023, 10, 23).unwrap();
+        let time = |hour| NaiveTime::from_hms_opt(hour, 0, 0).unwrap();
+
+        CalendarBuilder::new("TINF22B, Gruppe A")
+            .add_event(
+                EventBuilder::new(date, time(8), time(10), "Mathematik; Analysis")
+                    .location("A 1.01")
+                    .location("B 2.02")
+                    .organizer("Müller")
+                    .description("T3INF1001\nA 1.01, B 2.02")
+                    .category("Vorlesung")
+                    .rapla_url("https://rapla.dhbw.de/rapla/event?id=1&key=abc")
+                    .sequence(2)
+                    .build(),
+            )
+            .add_event(EventBuilder::new(date, time(22), time(1), "Labor").build())
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_from_ics_round_trip() {
+        let calendar = calendar();
+        let opts = ICSOptions {
+            reminder: Some(Duration::minutes(15)),
+            ..ICSOptions::default()
+        };
+
+        let ics = calendar.to_ics_with_options(opts).to_string();
+        assert_eq!(Calendar::from_ics(&ics).unwrap(), calendar);
+
+        let ics = calendar.to_ics_utc(chrono_tz::Europe::Berlin).to_string();
+        assert_eq!(Calendar::from_ics(&ics).unwrap(), calendar);
+    }
+
+    #[test]
+    fn test_from_ics_locations_with_separator() {
+        let mut calendar = calendar();
+        calendar.events[0].locations = vec![
+            "Audimax; Hörsaal 1".to_string(),
+            "Labor \\ 2".to_string(),
+            "B 2.02".to_string(),
+        ];
+
+        let ics = calendar.to_ics_string();
+        assert!(ics.contains(concat!(
+            r"LOCATION:Audimax\\\; Hörsaal 1\; Labor \\\\ 2\; B 2.02",
+            "\r\n"
+        )));
+        assert_eq!(Calendar::from_ics(&ics).unwrap(), calendar);
+    }
+
+    #[test]
+    fn test_from_ics_malformed() {
+        assert_eq!(Calendar::from_ics(""), Err(IcsParseError::MissingCalendar));
+        assert_eq!(
+            Calendar::from_ics("BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nSUMMARY:A\r\nEND:VEVENT\r\n"),
+            Err(IcsParseError::MissingProperty("DTSTART"))
+        );
+        assert!(matches!(
+            Calendar::from_ics("BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nDTSTART:2023\r\n"),
+            Err(IcsParseError::MalformedDateTime { .. })
+        ));
+    }
+}
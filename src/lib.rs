@@ -0,0 +1,103 @@
+pub mod builder;
+#[cfg(feature = "server")]
+pub mod cache;
+pub mod calendar;
+pub mod diff;
+#[cfg(feature = "encoding")]
+pub mod encoding;
+pub mod export;
+#[cfg(any(feature = "rss", feature = "atom"))]
+pub mod feed;
+#[cfg(any(feature = "fetch", feature = "async-fetch"))]
+pub mod fetch;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod import;
+#[cfg(feature = "server")]
+pub mod logging;
+pub mod parser;
+#[cfg(feature = "server")]
+pub mod proxy;
+pub mod recurrence;
+#[cfg(feature = "server")]
+pub mod resolver;
+pub mod semester;
+#[cfg(feature = "slack")]
+pub mod slack;
+pub mod stats;
+pub mod storage;
+pub mod timezone;
+pub mod url;
+pub mod validate;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, NaiveDate, NaiveTime};
+    use proptest::prelude::*;
+
+    use crate::calendar::{Calendar, Event};
+
+    prop_compose! {
+        fn arb_event()(
+            days in 0..3650i64,
+            start in 0..24 * 60u32,
+            end in 0..24 * 60u32,
+            title in "[a-zA-Z0-9äöü&<>,;:\\\\ ]{1,40}",
+            locations in prop::collection::vec("[A-Z] [0-9]\\.[0-9]{2}", 0..3),
+            organizer in prop::option::of("[a-zA-Zäöü, ]{1,20}"),
+            description in prop::option::of("[a-zA-Z0-9,;\n ]{1,100}"),
+            notes in prop::option::of("[a-zA-Z0-9,;\n ]{1,100}"),
+            category in prop::option::of("category_0[0-9]"),
+            rapla_url in prop::option::of("https://rapla\\.dhbw\\.de/rapla\\?id=[a-z0-9]{1,10}"),
+            sequence in 0..5u32,
+        ) -> Event {
+            let date = NaiveDate::from_ymd_opt(2020, 1, 1).unwrap() + Duration::days(days);
+            let start = NaiveTime::from_hms_opt(start / 60, start % 60, 0).unwrap();
+            let end = NaiveTime::from_hms_opt(end / 60, end % 60, 0).unwrap();
+            let end_date = if end < start { date + Duration::days(1) } else { date };
+
+            Event {
+                date, start, end, end_date, title, locations, organizer, description, notes,
+                category, rapla_url, sequence,
+            }
+        }
+    }
+
+    prop_compose! {
+        fn arb_calendar()(
+            name in "[a-zA-Z0-9 ]{1,20}",
+            events in prop::collection::vec(arb_event(), 0..20),
+        ) -> Calendar {
+            Calendar { name, events }
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn test_json_round_trip(calendar in arb_calendar()) {
+            let json = calendar.to_json().unwrap();
+            prop_assert_eq!(Calendar::from_json(&json).unwrap(), calendar);
+        }
+
+        #[test]
+        fn test_ics_lines(calendar in arb_calendar()) {
+            let ics = calendar.to_ics_string();
+            prop_assert_eq!(ics.matches("BEGIN:VEVENT\r\n").count(), calendar.events.len());
+
+            for line in ics.split_terminator("\r\n") {
+                // The ics crate folds after 75 bytes of content, continuation lines add a space to that.
+                prop_assert!(line.len() <= 76, "line is not folded: {line:?}");
+                prop_assert!(!line.contains('\n'), "unescaped newline: {line:?}");
+                prop_assert!(line.starts_with(' ') || line.contains(':'), "not a property: {line:?}");
+            }
+        }
+
+        #[test]
+        fn test_sort_is_sorted(mut calendar in arb_calendar()) {
+            calendar.sort();
+            prop_assert!(calendar.is_sorted());
+        }
+    }
+}
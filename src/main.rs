@@ -1,10 +1,3 @@
-mod cache;
-mod calendar;
-mod logging;
-mod parser;
-mod proxy;
-mod resolver;
-
 use std::io;
 use std::net::SocketAddr;
 
@@ -15,7 +8,8 @@ use tokio::net::TcpListener;
 use tokio::signal;
 use tokio::time::Duration;
 
-use crate::resolver::UpstreamUrlComponents;
+use rapla_ical_proxy::resolver::{self, UpstreamUrlComponents};
+use rapla_ical_proxy::{cache, logging, proxy};
 
 #[derive(Parser)]
 struct Args {
@@ -54,17 +48,17 @@ async fn main() -> io::Result<()> {
     eprintln!("Cache time to live:      {}s", args.cache_ttl);
     eprintln!("Cache max size:          {}mb", args.cache_max_size);
 
-    let cache_config = crate::cache::Config {
+    let cache_config = cache::Config {
         ttl: Duration::from_secs(args.cache_ttl),
         max_size: args.cache_max_size,
     };
 
     // Middlewares are layered, i.e. the later it is applied the earlier it is called.
     let router = Router::new();
-    let router = crate::proxy::apply_routes(router);
-    let router = crate::cache::apply_middleware(router, cache_config);
-    let router = crate::resolver::apply_middleware(router);
-    let router = crate::logging::apply_middleware(router);
+    let router = proxy::apply_routes(router);
+    let router = cache::apply_middleware(router, cache_config);
+    let router = resolver::apply_middleware(router);
+    let router = logging::apply_middleware(router);
 
     let listener = TcpListener::bind(args.address).await?;
     axum::serve(listener, router)
@@ -80,8 +74,8 @@ async fn debug(uri: Uri) {
         .expect("couldn't resolve upstream")
         .generate_url();
 
-    let client = crate::proxy::build_client();
-    let calendar = crate::proxy::handle(&client, upstream)
+    let client = proxy::build_client();
+    let calendar = proxy::handle(&client, upstream)
         .await
         .expect("couldn't handle request");
 
@@ -1,7 +1,11 @@
+use std::fmt;
+use std::io::{self, Read};
 use std::ops::Not;
 
-use chrono::{Duration, NaiveDate, NaiveTime};
+use chrono::{Datelike, Duration, NaiveDate, NaiveTime, Utc, Weekday};
+use chrono_tz::Tz;
 use html_escape::decode_html_entities;
+use http::Uri;
 use once_cell::sync::Lazy;
 use scraper::{ElementRef, Html, Selector};
 
@@ -49,119 +53,749 @@ macro_rules! trace_err {
     };
 }
 
-macro_rules! select {
-    ($element:expr, $query:expr) => {{
-        static SELECTOR: Lazy<Selector> = Lazy::new(|| Selector::parse($query).unwrap());
-        $element.select(&SELECTOR)
-    }};
+/// Forwards to the `tracing` or `log` macro of the same level, compiles to nothing without either feature.
+/// If both features are enabled only `tracing` is used, so messages aren't logged twice.
+macro_rules! log {
+    ($level:ident, $($arg:tt)+) => {
+        #[cfg(feature = "tracing")]
+        tracing::$level!($($arg)+);
+        #[cfg(all(feature = "log", not(feature = "tracing")))]
+        log::$level!($($arg)+);
+    };
+}
+
+/// CSS selectors used to find the parts of a page, institutions with customized Rapla themes may need to adjust these.
+/// The defaults match the pages served by DHBW.
+#[derive(Debug, Clone)]
+pub struct Selectors {
+    /// The selected option of the year dropdown, see [`Calendar::try_from`].
+    pub start_year: Selector,
+    pub title: Selector,
+    /// Used for the name if the title is empty.
+    pub heading: Selector,
+    /// One element per resource shown on the page.
+    pub calendar: Selector,
+    /// Matched within a calendar.
+    pub weeks: Selector,
+    pub week_number: Selector,
+    /// Wraps the date inside the week header on some versions of Rapla.
+    pub week_header_date: Selector,
+    pub week_header: Selector,
+    pub week_row: Selector,
+    pub week_column: Selector,
+    /// The innermost match is used.
+    pub event_details: Selector,
+    pub event_resource: Selector,
+    pub event_person: Selector,
+    /// Comments that aren't shown in the calendar view, including those inside the tooltip.
+    pub event_notes: Selector,
+    /// Its `href` links to the details of the event.
+    pub event_link: Selector,
+    /// Fields of the date form that only pages requested with newer links have, see [`RaplaVariant`].
+    pub goto_form: Selector,
+}
+
+impl Default for Selectors {
+    fn default() -> Self {
+        let parse = |query| Selector::parse(query).expect("default selectors should be valid");
+        Self {
+            start_year: parse("select[name=year] > option[selected]"),
+            title: parse("title"),
+            heading: parse("h2"),
+            calendar: parse("div.calendar"),
+            weeks: parse(":scope > table.week_table > tbody"),
+            week_number: parse("th.week_number"),
+            week_header_date: parse("tr > td.week_header > nobr"),
+            week_header: parse("tr > td.week_header"),
+            week_row: parse("tr"),
+            week_column: parse("td"),
+            event_details: parse(":is(a, span.link)"),
+            event_resource: parse("span.resource"),
+            event_person: parse("span.person"),
+            event_notes: parse("span.comment"),
+            event_link: parse("a[href]"),
+            goto_form: parse("form [name=salt], form [name=goto]"),
+        }
+    }
+}
+
+static DEFAULT_SELECTORS: Lazy<Selectors> = Lazy::new(Selectors::default);
+
+/// All options for parsing a page, see [`Calendar::from_html_with_config`].
+#[derive(Debug, Clone)]
+pub struct ParserConfig {
+    selectors: Selectors,
+    strict: bool,
+    timezone: Tz,
+}
+
+impl Default for ParserConfig {
+    fn default() -> Self {
+        Self {
+            selectors: DEFAULT_SELECTORS.clone(),
+            strict: false,
+            timezone: chrono_tz::Europe::Berlin,
+        }
+    }
+}
+
+impl ParserConfig {
+    #[must_use]
+    pub fn selectors(mut self, selectors: Selectors) -> Self {
+        self.selectors = selectors;
+        self
+    }
+
+    /// Fail on the first event that can't be parsed instead of skipping it, and on pages that
+    /// don't name the year they start in.
+    #[must_use]
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Used to tell the current year if the page doesn't name the year it starts in.
+    #[must_use]
+    pub fn timezone(mut self, timezone: Tz) -> Self {
+        self.timezone = timezone;
+        self
+    }
+}
+
+#[derive(Debug)]
+pub enum ParseError {
+    MissingTitle,
+    MissingWeekNumber,
+    MalformedWeekNumber {
+        raw: String,
+    },
+    MissingWeekHeader,
+    MalformedDate {
+        raw: String,
+    },
+    MissingColumnClass,
+    MissingEventDetails,
+    MalformedTime {
+        raw: String,
+    },
+    MissingEventTitle,
+    MissingStartYear,
+    /// [`parse_calendar_pages`] was given no pages at all.
+    NoPages,
+    DateOutOfRange {
+        date: NaiveDate,
+        last_day: NaiveDate,
+    },
+    InvalidUtf8(std::str::Utf8Error),
+    Io(io::Error),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingTitle => write!(f, "missing calendar title"),
+            Self::MissingWeekNumber => write!(f, "missing week number"),
+            Self::MalformedWeekNumber { raw } => write!(f, "malformed week number: {raw:?}"),
+            Self::MissingWeekHeader => write!(f, "missing week header"),
+            Self::MalformedDate { raw } => write!(f, "malformed date: {raw:?}"),
+            Self::MissingColumnClass => write!(f, "missing column class"),
+            Self::MissingEventDetails => write!(f, "missing event details"),
+            Self::MalformedTime { raw } => write!(f, "malformed time: {raw:?}"),
+            Self::MissingEventTitle => write!(f, "missing event title"),
+            Self::MissingStartYear => write!(f, "missing selected year"),
+            Self::NoPages => write!(f, "no pages to parse"),
+            Self::DateOutOfRange { date, last_day } => {
+                write!(
+                    f,
+                    "computed date {date} is past the end of the week on {last_day}"
+                )
+            }
+            Self::InvalidUtf8(err) => write!(f, "invalid utf-8: {err}"),
+            Self::Io(err) => write!(f, "can't read calendar: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::InvalidUtf8(err) => Some(err),
+            Self::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+/// An event that was skipped by [`parse_calendar_lossy`].
+#[derive(Debug)]
+pub struct ParseWarning {
+    pub date: NaiveDate,
+    pub error: ParseError,
+}
+
+impl fmt::Display for ParseWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "skipped event on {}: {}", self.date, self.error)
+    }
+}
+
+/// Which kind of link the page was requested with, newer links carry a `salt` and a `goto` parameter
+/// which are repeated in the date form of the page.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RaplaVariant {
+    OldFormat,
+    NewFormat,
+}
+
+/// Details about how a page was parsed, see [`parse_calendar_with_context`].
+#[derive(Debug)]
+pub struct ParseContext {
+    pub variant: RaplaVariant,
+    pub warnings: Vec<ParseWarning>,
+}
+
+/// Fails only on structural problems, events that can't be parsed are skipped.
+pub fn parse_calendar(s: &str, start_year: i32) -> Result<Calendar, ParseError> {
+    parse_calendar_lossy(s, start_year).map(|(calendar, _)| calendar)
+}
+
+/// Like [`parse_calendar`], but also reports every event that had to be skipped.
+pub fn parse_calendar_lossy(
+    s: &str,
+    start_year: i32,
+) -> Result<(Calendar, Vec<ParseWarning>), ParseError> {
+    parse_calendar_with_context(s, start_year)
+        .map(|(calendar, context)| (calendar, context.warnings))
+}
+
+/// Like [`parse_calendar_lossy`], with additional diagnostics about the page.
+pub fn parse_calendar_with_context(
+    s: &str,
+    start_year: i32,
+) -> Result<(Calendar, ParseContext), ParseError> {
+    parse_calendar_with_selectors(s, start_year, &DEFAULT_SELECTORS)
 }
 
-pub fn parse_calendar(s: &str, mut start_year: i32) -> Option<Calendar> {
+/// Like [`parse_calendar_with_context`], but finds the parts of the page with custom selectors.
+pub fn parse_calendar_with_selectors(
+    s: &str,
+    start_year: i32,
+    selectors: &Selectors,
+) -> Result<(Calendar, ParseContext), ParseError> {
     let html = Html::parse_document(s);
-    let name = select!(html, "title")
+    let (calendar, warnings) = parse_document_lossy(&html, start_year, selectors)?;
+    let context = ParseContext {
+        variant: detect_variant(&html, selectors),
+        warnings,
+    };
+    Ok((calendar, context))
+}
+
+fn detect_variant(html: &Html, selectors: &Selectors) -> RaplaVariant {
+    if html.select(&selectors.goto_form).next().is_some() {
+        RaplaVariant::NewFormat
+    } else {
+        RaplaVariant::OldFormat
+    }
+}
+
+/// The year of the first displayed week, as selected in the date form above the calendar.
+fn parse_start_year(html: &Html, selectors: &Selectors) -> Result<i32, ParseError> {
+    html.select(&selectors.start_year)
         .next()
-        .inspect_none(trace_none!())?
-        .inner_html()
-        .trim()
-        .to_string();
+        .inspect_none(trace_none!())
+        .and_then(|option| {
+            option
+                .inner_html()
+                .trim()
+                .parse::<i32>()
+                .inspect_err(trace_err!())
+                .ok()
+        })
+        .ok_or(ParseError::MissingStartYear)
+}
+
+/// The page title names the calendar, with a heading as fallback in case the title is left empty.
+fn parse_name(html: &Html, selectors: &Selectors) -> Result<String, ParseError> {
+    let names = html
+        .select(&selectors.title)
+        .chain(html.select(&selectors.heading))
+        .map(|element| decode_html_entities(element.inner_html().trim()).to_string())
+        .collect::<Vec<_>>();
+
+    names
+        .iter()
+        .find(|name| !name.is_empty())
+        .or(names.first())
+        .cloned()
+        .inspect_none(trace_none!())
+        .ok_or(ParseError::MissingTitle)
+}
+
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+fn parse_document_lossy(
+    html: &Html,
+    start_year: i32,
+    selectors: &Selectors,
+) -> Result<(Calendar, Vec<ParseWarning>), ParseError> {
+    let name = parse_name(html, selectors)?;
 
     let mut events = Vec::new();
-    for (idx, week_element) in select!(html, "div.calendar > table.week_table > tbody").enumerate()
-    {
-        let week_number_html = select!(week_element, "th.week_number")
+    let mut warnings = Vec::new();
+    for group in html.select(&selectors.calendar) {
+        events.append(&mut parse_group(
+            group,
+            start_year,
+            selectors,
+            &mut warnings,
+        )?);
+    }
+
+    log!(
+        debug,
+        "parsed {} events with {} warnings",
+        events.len(),
+        warnings.len()
+    );
+    Ok((Calendar { name, events }, warnings))
+}
+
+/// The weeks of a single resource, pages showing several resources have one group per resource.
+/// Each group starts over at the first displayed week.
+fn parse_group(
+    group: ElementRef,
+    start_year: i32,
+    selectors: &Selectors,
+    warnings: &mut Vec<ParseWarning>,
+) -> Result<Vec<Event>, ParseError> {
+    let weeks = group.select(&selectors.weeks).collect::<Vec<_>>();
+    log!(
+        debug,
+        "found {} weeks starting in {start_year}",
+        weeks.len()
+    );
+
+    let mut events = Vec::new();
+    let mut previous_monday: Option<NaiveDate> = None;
+    for week_element in weeks {
+        let week_number_html = week_element
+            .select(&selectors.week_number)
             .next()
-            .inspect_none(trace_none!())?
+            .inspect_none(trace_none!())
+            .ok_or(ParseError::MissingWeekNumber)?
             .inner_html();
 
-        let week_number = week_number_html
-            .split(' ')
-            .nth(1)
-            .inspect_none(trace_none!(&week_number_html))?
-            .parse::<usize>()
-            .inspect_err(trace_err!())
-            .ok()?;
+        let week_number = parse_week_number(&week_number_html)
+            .inspect_none(trace_none!(&week_number_html))
+            .ok_or_else(|| ParseError::MalformedWeekNumber {
+                raw: week_number_html.clone(),
+            })?;
 
-        if week_number == 1 && idx > 0 {
-            start_year += 1;
-        }
+        let header = parse_week_header(week_element, selectors)?;
+        let year = match previous_monday {
+            // Weeks are shown in order, one that starts earlier in the year than the one
+            // before starts a new year. Unlike looking for week 1, this also works if
+            // week 1 starts in december or isn't shown at all.
+            Some(previous) if (header.month, header.day) < (previous.month(), previous.day()) => {
+                log!(debug, "week {week_number} starts a new year");
+                previous.year().saturating_add(1)
+            }
+            Some(previous) => previous.year(),
+            // The year dropdown shows the year of the picked date, which may lie in january
+            // while the first week started in december.
+            None => [start_year, start_year.saturating_sub(1)]
+                .into_iter()
+                .find(|&year| header.is_monday_of_week(year, week_number))
+                .unwrap_or(start_year),
+        };
+        let monday = header.date(year)?;
+        previous_monday = Some(monday);
 
-        let mut week_events = parse_week(week_element, start_year).inspect_none(trace_none!())?;
+        log!(trace, "parsing week {week_number} starting on {monday}");
+        let mut week_events = parse_week(week_element, &header, monday, selectors, warnings)?;
         events.append(&mut week_events);
     }
 
-    Some(Calendar { name, events })
+    Ok(events)
+}
+
+/// Week numbers usually read "KW 43", some themes separate them with a non-breaking space or show the bare number.
+fn parse_week_number(raw: &str) -> Option<usize> {
+    let decoded = decode_html_entities(raw);
+    let strategies: [fn(&str) -> Option<&str>; 3] = [
+        |raw| raw.split(' ').nth(1),
+        |raw| raw.split('\u{a0}').nth(1),
+        |raw| Some(raw),
+    ];
+
+    strategies.iter().enumerate().find_map(|(idx, strategy)| {
+        let week_number = strategy(&decoded)?.trim().parse::<usize>().ok()?;
+        if idx > 0 {
+            log!(warn, "week number {raw:?} only parsed with fallback {idx}");
+        }
+        Some(week_number)
+    })
+}
+
+/// Reads the start year from the page itself.
+impl TryFrom<&Html> for Calendar {
+    type Error = ParseError;
+
+    fn try_from(html: &Html) -> Result<Self, Self::Error> {
+        Self::from_document_with_selectors(html, &DEFAULT_SELECTORS)
+    }
+}
+
+impl TryFrom<Html> for Calendar {
+    type Error = ParseError;
+
+    fn try_from(html: Html) -> Result<Self, Self::Error> {
+        Self::try_from(&html)
+    }
+}
+
+impl TryFrom<&str> for Calendar {
+    type Error = ParseError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        Self::try_from(&Html::parse_document(s))
+    }
+}
+
+impl TryFrom<&[u8]> for Calendar {
+    type Error = ParseError;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        Self::try_from(std::str::from_utf8(bytes).map_err(ParseError::InvalidUtf8)?)
+    }
+}
+
+impl Calendar {
+    /// Like [`Calendar::try_from`], but finds the parts of the page with custom selectors.
+    pub fn from_html_with_selectors(s: &str, selectors: &Selectors) -> Result<Self, ParseError> {
+        Self::from_document_with_selectors(&Html::parse_document(s), selectors)
+    }
+
+    /// Like [`Calendar::try_from`], with every option of the parser configurable in one place.
+    pub fn from_html_with_config(s: &str, config: &ParserConfig) -> Result<Self, ParseError> {
+        let html = Html::parse_document(s);
+        let start_year = match parse_start_year(&html, &config.selectors) {
+            Ok(start_year) => start_year,
+            Err(ParseError::MissingStartYear) if !config.strict => {
+                Utc::now().with_timezone(&config.timezone).year()
+            }
+            Err(err) => return Err(err),
+        };
+
+        let (calendar, warnings) = parse_document_lossy(&html, start_year, &config.selectors)?;
+        match warnings.into_iter().next() {
+            Some(warning) if config.strict => Err(warning.error),
+            _ => Ok(calendar),
+        }
+    }
+
+    fn from_document_with_selectors(
+        html: &Html,
+        selectors: &Selectors,
+    ) -> Result<Self, ParseError> {
+        let start_year = parse_start_year(html, selectors)?;
+        parse_document_lossy(html, start_year, selectors).map(|(calendar, _)| calendar)
+    }
+
+    /// One calendar per resource shown on the page, all named after the page.
+    /// The start year is read from the page itself, like [`Calendar::try_from`] does.
+    pub fn all_from_html(s: &str) -> Result<Vec<Calendar>, ParseError> {
+        let html = Html::parse_document(s);
+        let selectors = &*DEFAULT_SELECTORS;
+        let name = parse_name(&html, selectors)?;
+        let start_year = parse_start_year(&html, selectors)?;
+
+        let mut warnings = Vec::new();
+        html.select(&selectors.calendar)
+            .map(|group| {
+                Ok(Calendar {
+                    name: name.clone(),
+                    events: parse_group(group, start_year, selectors, &mut warnings)?,
+                })
+            })
+            .collect()
+    }
+
+    /// Reads a whole document from the reader, see [`Calendar::try_from`].
+    pub fn from_reader<R: Read>(mut reader: R) -> Result<Self, ParseError> {
+        let mut buf = Vec::new();
+        reader.read_to_end(&mut buf).map_err(ParseError::Io)?;
+        Self::try_from(buf.as_slice())
+    }
+
+    #[cfg(feature = "async-fetch")]
+    pub async fn from_reader_async<R>(mut reader: R) -> Result<Self, ParseError>
+    where
+        R: tokio::io::AsyncRead + Unpin,
+    {
+        use tokio::io::AsyncReadExt;
+
+        let mut buf = Vec::new();
+        reader.read_to_end(&mut buf).await.map_err(ParseError::Io)?;
+        Self::try_from(buf.as_slice())
+    }
+}
+
+/// Parses several pages of the same calendar, each paired with the year its first week starts in.
+/// Pages that fail to parse are skipped, the error of the first one is returned if none succeed.
+/// Events shown on several pages are only kept once.
+///
+/// With the `parallel` feature, pages are parsed on the rayon thread pool. Weeks within a page
+/// can't be parsed in parallel as the parsed document is not thread-safe.
+pub fn parse_calendar_pages<I, S>(pages: I) -> Result<Calendar, ParseError>
+where
+    I: IntoIterator<Item = (S, i32)>,
+    S: AsRef<str> + Send,
+{
+    #[cfg(feature = "parallel")]
+    let results = {
+        use rayon::iter::{IntoParallelIterator, ParallelIterator};
+        pages
+            .into_iter()
+            .collect::<Vec<_>>()
+            .into_par_iter()
+            .map(|(page, start_year)| parse_calendar(page.as_ref(), start_year))
+            .collect::<Vec<_>>()
+    };
+
+    #[cfg(not(feature = "parallel"))]
+    let results = pages
+        .into_iter()
+        .map(|(page, start_year)| parse_calendar(page.as_ref(), start_year));
+
+    let mut calendar: Option<Calendar> = None;
+    let mut first_error = None;
+
+    for result in results {
+        match result {
+            Ok(parsed) => {
+                calendar = Some(match calendar {
+                    Some(calendar) => calendar.merge_dedup(parsed),
+                    None => parsed,
+                });
+            }
+            Err(err) => {
+                first_error.get_or_insert(err);
+            }
+        }
+    }
+
+    calendar.ok_or_else(|| first_error.unwrap_or(ParseError::NoPages))
+}
+
+/// The last day shown for the week, taken from the last day header or the end of a range like "22.10.–28.10.".
+/// A single header without a range says nothing about where the week ends.
+fn parse_last_day(
+    element: ElementRef,
+    monday: NaiveDate,
+    selectors: &Selectors,
+) -> Option<NaiveDate> {
+    let headers = element
+        .select(&selectors.week_header)
+        .map(|header| header.text().collect::<String>())
+        .collect::<Vec<_>>();
+
+    let last = headers.last()?;
+    let (_, end) = last
+        .rsplit_once(['–', '-'])
+        .or_else(|| (headers.len() > 1).then_some(("", last.as_str())))?;
+
+    let mut day_month = end
+        .split_whitespace()
+        .last()?
+        .trim_end_matches('.')
+        .split('.');
+    let day = day_month.next()?.parse::<u32>().ok()?;
+    let month = day_month.next()?.parse::<u32>().ok()?;
+
+    // Weeks may end in the next year.
+    let year = if month < monday.month() {
+        monday.year() + 1
+    } else {
+        monday.year()
+    };
+    NaiveDate::from_ymd_opt(year, month, day)
+}
+
+/// Day and month the week starts on, the header doesn't include the year.
+struct WeekHeader {
+    raw: String,
+    day: u32,
+    month: u32,
+}
+
+impl WeekHeader {
+    fn malformed(&self) -> ParseError {
+        ParseError::MalformedDate {
+            raw: self.raw.clone(),
+        }
+    }
+
+    fn date(&self, year: i32) -> Result<NaiveDate, ParseError> {
+        NaiveDate::from_ymd_opt(year, self.month, self.day)
+            .inspect_none(trace_none!())
+            .ok_or_else(|| self.malformed())
+    }
+
+    fn is_monday_of_week(&self, year: i32, week_number: usize) -> bool {
+        self.date(year).is_ok_and(|date| {
+            date.weekday() == Weekday::Mon
+                && usize::try_from(date.iso_week().week()) == Ok(week_number)
+        })
+    }
 }
 
-fn parse_week(element: ElementRef, start_year: i32) -> Option<Vec<Event>> {
-    let week_header = select!(element, "tr > td.week_header > nobr")
+fn parse_week_header(element: ElementRef, selectors: &Selectors) -> Result<WeekHeader, ParseError> {
+    // Not every version of Rapla wraps the date in a <nobr>.
+    let raw = element
+        .select(&selectors.week_header_date)
         .next()
-        .inspect_none(trace_none!())?
+        .or_else(|| element.select(&selectors.week_header).next())
+        .inspect_none(trace_none!())
+        .ok_or(ParseError::MissingWeekHeader)?
         .inner_html();
 
-    let mut day_month = week_header
-        .split(' ')
+    let malformed_date = || ParseError::MalformedDate { raw: raw.clone() };
+
+    // Some versions separate weekday and date with a non-breaking space.
+    let decoded = decode_html_entities(&raw);
+    let mut day_month = decoded
+        .split_whitespace()
         .nth(1)
-        .inspect_none(trace_none!())?
+        .inspect_none(trace_none!())
+        .ok_or_else(malformed_date)?
         .trim_end_matches('.')
         .split('.');
 
-    let start_day = day_month
+    let day = day_month
         .next()
-        .inspect_none(trace_none!())?
-        .parse::<u32>()
-        .inspect_err(trace_err!())
-        .ok()?;
+        .inspect_none(trace_none!())
+        .and_then(|raw| raw.parse::<u32>().inspect_err(trace_err!()).ok())
+        .ok_or_else(malformed_date)?;
 
-    let start_month = day_month
-        .next()?
-        .parse::<u32>()
-        .inspect_err(trace_err!())
-        .ok()?;
+    let month = day_month
+        .next()
+        .inspect_none(trace_none!())
+        .and_then(|raw| raw.parse::<u32>().inspect_err(trace_err!()).ok())
+        .ok_or_else(malformed_date)?;
 
-    let monday =
-        NaiveDate::from_ymd_opt(start_year, start_month, start_day).inspect_none(trace_none!())?;
+    Ok(WeekHeader { raw, day, month })
+}
+
+fn parse_week(
+    element: ElementRef,
+    header: &WeekHeader,
+    monday: NaiveDate,
+    selectors: &Selectors,
+    warnings: &mut Vec<ParseWarning>,
+) -> Result<Vec<Event>, ParseError> {
+    let malformed_date = || header.malformed();
+
+    let last_day = parse_last_day(element, monday, selectors);
 
     let mut events = Vec::new();
-    for row in select!(element, "tr").skip(1) {
+    for row in element.select(&selectors.week_row).skip(1) {
         let mut day_index = 0;
-        for column in select!(row, "td") {
-            let class = column
-                .value()
-                .classes()
-                .next()
-                .inspect_none(trace_none!())?;
-
-            if class.starts_with("week_separatorcell") {
+        for column in row.select(&selectors.week_column) {
+            // Classes come sorted, event blocks may carry more than just `week_block`.
+            let classes = column.value().classes().collect::<Vec<_>>();
+            classes
+                .first()
+                .inspect_none(trace_none!())
+                .ok_or(ParseError::MissingColumnClass)?;
+
+            if classes
+                .iter()
+                .any(|class| class.starts_with("week_separatorcell"))
+            {
                 day_index += 1;
             }
 
-            if class != "week_block" {
+            let is_block = classes.contains(&"week_block");
+            if !is_block {
+                continue;
+            }
+
+            let date = Duration::try_days(day_index)
+                .and_then(|offset| monday.checked_add_signed(offset))
+                .inspect_none(trace_none!())
+                .ok_or_else(malformed_date)?;
+
+            // More columns than days shown means the day arithmetic is off, the date can't be trusted.
+            if let Some(last_day) = last_day.filter(|last_day| date > *last_day) {
+                log!(
+                    warn,
+                    "computed date {date} is past the end of the week on {last_day}"
+                );
+                warnings.push(ParseWarning {
+                    date,
+                    error: ParseError::DateOutOfRange { date, last_day },
+                });
                 continue;
             }
 
-            let date = monday + Duration::try_days(day_index).inspect_none(trace_none!())?;
-            events.push(parse_event(column, date).inspect_none(trace_none!())?);
+            match parse_event(column, date, selectors) {
+                Ok(event) => {
+                    log!(debug, "parsed event {:?} on {date}", event.title);
+                    events.push(event);
+                }
+                Err(error) => {
+                    log!(
+                        warn,
+                        "can't parse event on {date}: {error}\n{}",
+                        column.html()
+                    );
+                    warnings.push(ParseWarning { date, error });
+                }
+            }
         }
     }
 
-    Some(events)
+    Ok(events)
+}
+
+fn parse_time(raw: &str) -> Result<NaiveTime, ParseError> {
+    NaiveTime::parse_from_str(raw, "%H:%M")
+        .inspect_err(trace_err!())
+        .map_err(|_| ParseError::MalformedTime {
+            raw: raw.to_string(),
+        })
 }
 
-fn parse_event(element: ElementRef, date: NaiveDate) -> Option<Event> {
+fn parse_event(
+    element: ElementRef,
+    date: NaiveDate,
+    selectors: &Selectors,
+) -> Result<Event, ParseError> {
     // Sometimes there is an extra <span class="link"> wrapper around the content we're after.
     // We pick last element to ensure we have the innermost matched element.
-    let details = select!(element, ":is(a, span.link)")
+    let details = element
+        .select(&selectors.event_details)
         .last()
-        .inspect_none(trace_none!())?
+        .inspect_none(trace_none!())
+        .ok_or(ParseError::MissingEventDetails)?
         .inner_html();
 
     let mut details_split = details.split("<br>");
 
-    let times_raw = details_split.next().inspect_none(trace_none!())?;
+    let times_raw = details_split
+        .next()
+        .inspect_none(trace_none!())
+        .ok_or(ParseError::MissingEventDetails)?;
     let mut times_raw_split = times_raw.split("&nbsp;-");
 
-    let start_time_raw = times_raw_split.next().inspect_none(trace_none!())?;
-    let end_time_raw = times_raw_split.next().inspect_none(trace_none!())?;
+    let (start_time_raw, end_time_raw) = times_raw_split
+        .next()
+        .zip(times_raw_split.next())
+        .inspect_none(trace_none!())
+        .ok_or_else(|| ParseError::MalformedTime {
+            raw: times_raw.to_string(),
+        })?;
 
     // Some genuises at DHBW find it a great idea to leave out the start and/or end time
     // to signify "full day" which is to be interpreted as "from 08:00 until 18:00".
@@ -169,39 +803,544 @@ fn parse_event(element: ElementRef, date: NaiveDate) -> Option<Event> {
     let start = if start_time_raw.is_empty() {
         NaiveTime::from_hms_opt(8, 0, 0).unwrap()
     } else {
-        NaiveTime::parse_from_str(start_time_raw, "%H:%M")
-            .inspect_err(trace_err!())
-            .ok()?
+        parse_time(start_time_raw)?
     };
     let end = if end_time_raw.is_empty() {
         NaiveTime::from_hms_opt(18, 0, 0).unwrap()
     } else {
-        NaiveTime::parse_from_str(end_time_raw, "%H:%M")
-            .inspect_err(trace_err!())
-            .ok()?
+        parse_time(end_time_raw)?
+    };
+    // Late lab sessions may run past midnight, the end time then belongs to the next day.
+    let end_date = if end < start {
+        date.succ_opt()
+            .inspect_none(trace_none!())
+            .ok_or_else(|| ParseError::MalformedTime {
+                raw: times_raw.to_string(),
+            })?
+    } else {
+        date
     };
 
-    let title = details_split.next().inspect_none(trace_none!())?;
+    let title = details_split
+        .next()
+        .inspect_none(trace_none!())
+        .ok_or(ParseError::MissingEventTitle)?;
     let title = decode_html_entities(title).to_string();
 
-    let resources = select!(element, "span.resource")
+    let locations = element
+        .select(&selectors.event_resource)
         .map(|location| decode_html_entities(&location.inner_html()).to_string())
         .collect::<Vec<_>>();
-    let location = resources.last().cloned();
-    let description = resources.is_empty().not().then(|| resources.join(", "));
 
-    let persons = select!(element, "span.person")
+    // Anything below the title are free-form details such as module codes or notes.
+    // Only plain text is kept, resources and persons are handled separately.
+    let mut description_lines = details_split
+        .map(|fragment| {
+            Html::parse_fragment(fragment)
+                .root_element()
+                .children()
+                .filter_map(|node| node.value().as_text().map(|text| text.to_string()))
+                .collect::<String>()
+                .trim()
+                .to_string()
+        })
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>();
+    if !locations.is_empty() {
+        description_lines.push(locations.join(", "));
+    }
+    let description = description_lines
+        .is_empty()
+        .not()
+        .then(|| description_lines.join("\n"));
+
+    let persons = element
+        .select(&selectors.event_person)
         .map(|person| decode_html_entities(&person.inner_html()).to_string())
         .collect::<Vec<_>>();
     let organizer = persons.is_empty().not().then(|| persons.join(", "));
 
-    Some(Event {
+    let notes = element
+        .select(&selectors.event_notes)
+        .map(|note| note.text().collect::<String>().trim().to_string())
+        .filter(|note| !note.is_empty())
+        .collect::<Vec<_>>();
+    let notes = notes.is_empty().not().then(|| notes.join("\n"));
+
+    // Institutions can configure extra classes such as `category_03` to tell event types apart.
+    let category = element
+        .value()
+        .classes()
+        .find(|class| *class != "week_block")
+        .map(str::to_string);
+
+    // Only absolute links are of any use outside of Rapla, anything else like `javascript:` is dropped.
+    let rapla_url = element
+        .select(&selectors.event_link)
+        .next()
+        .and_then(|link| link.value().attr("href"))
+        .filter(|href| {
+            href.parse::<Uri>()
+                .is_ok_and(|uri| matches!(uri.scheme_str(), Some("http" | "https")))
+        })
+        .map(str::to_string);
+
+    Ok(Event {
         date,
         start,
         end,
+        end_date,
         title,
-        location,
+        locations,
         organizer,
         description,
+        notes,
+        category,
+        rapla_url,
+        sequence: 0,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, NaiveTime};
+
+    use scraper::Selector;
+
+    use super::{
+        parse_calendar, parse_calendar_lossy, parse_calendar_pages, parse_calendar_with_context,
+        parse_calendar_with_selectors, ParseError, ParserConfig, RaplaVariant, Selectors,
+    };
+    use crate::calendar::Calendar;
+
+    const CALENDAR: &str = r#"<html>
+<head><title> TINF22B </title></head>
+<body><form><select name="year"><option>2022</option><option selected>2023</option></select></form>
+<div class="calendar"><table class="week_table"><tbody>
+<tr><th class="week_number">KW 43</th><td class="week_header"><nobr>Mo 23.10.</nobr></td></tr>
+<tr>
+<td class="week_times">08:00</td>
+<td class="week_block category_02"><a href="https://rapla.dhbw.de/rapla/event?id=1&amp;key=abc">08:30&nbsp;-10:00<br>Mathematik &amp; Statistik<br>T3INF1001<br><span class="resource">A 1.01</span><span class="resource">B 2.02</span><span class="person">Müller</span></a></td>
+<td class="week_separatorcell"></td>
+<td class="week_block"><a href="javascript:void(0)">&nbsp;-<br>Projekt</a></td>
+</tr>
+</tbody></table></div></body>
+</html>"#;
+
+    #[test]
+    fn test_parse_calendar() {
+        let calendar = parse_calendar(CALENDAR, 2023).unwrap();
+        assert_eq!(calendar.name, "TINF22B");
+        assert_eq!(calendar.events.len(), 2);
+
+        let event = &calendar.events[0];
+        assert_eq!(event.date, NaiveDate::from_ymd_opt(2023, 10, 23).unwrap());
+        assert_eq!(event.start, NaiveTime::from_hms_opt(8, 30, 0).unwrap());
+        assert_eq!(event.end, NaiveTime::from_hms_opt(10, 0, 0).unwrap());
+        assert_eq!(event.title, "Mathematik & Statistik");
+        assert_eq!(event.locations, ["A 1.01", "B 2.02"]);
+        assert_eq!(event.instructor(), Some("Müller"));
+        assert_eq!(event.category.as_deref(), Some("category_02"));
+        assert_eq!(
+            event.rapla_url.as_deref(),
+            Some("https://rapla.dhbw.de/rapla/event?id=1&key=abc")
+        );
+        assert_eq!(
+            event.description.as_deref(),
+            Some("T3INF1001\nA 1.01, B 2.02")
+        );
+
+        let event = &calendar.events[1];
+        assert_eq!(event.date, NaiveDate::from_ymd_opt(2023, 10, 24).unwrap());
+        assert_eq!(event.start, NaiveTime::from_hms_opt(8, 0, 0).unwrap());
+        assert_eq!(event.end, NaiveTime::from_hms_opt(18, 0, 0).unwrap());
+        assert_eq!(event.description, None);
+        assert_eq!(event.category, None);
+        assert_eq!(event.rapla_url, None);
+        assert_eq!(event.notes, None);
+    }
+
+    #[test]
+    fn test_parse_calendar_pages() {
+        let next_week = CALENDAR
+            .replace("KW 43", "KW 44")
+            .replace("Mo 23.10.", "Mo 30.10.");
+        let calendar =
+            parse_calendar_pages([(CALENDAR, 2023), (&next_week, 2023), (CALENDAR, 2023)]).unwrap();
+        assert_eq!(calendar.name, "TINF22B");
+        assert_eq!(calendar.events.len(), 4);
+
+        let calendar = parse_calendar_pages([("", 2023), (CALENDAR, 2023)]).unwrap();
+        assert_eq!(calendar.events.len(), 2);
+
+        assert!(matches!(
+            parse_calendar_pages([("", 2023)]),
+            Err(ParseError::MissingTitle)
+        ));
+        assert!(matches!(
+            parse_calendar_pages(Vec::<(&str, i32)>::new()),
+            Err(ParseError::NoPages)
+        ));
+    }
+
+    #[test]
+    fn test_parse_calendar_instructors() {
+        let html = CALENDAR.replace(
+            "<span class=\"person\">Müller</span>",
+            "<span class=\"person\">Müller</span><span class=\"person\">Schmidt &amp; Partner</span>",
+        );
+        let calendar = parse_calendar(&html, 2023).unwrap();
+        assert_eq!(
+            calendar.events[0].instructor(),
+            Some("Müller, Schmidt & Partner")
+        );
+        assert_eq!(calendar.events[1].instructor(), None);
+    }
+
+    #[test]
+    fn test_parse_calendar_notes() {
+        let html = CALENDAR.replace(
+            "<span class=\"person\">Müller</span>",
+            concat!(
+                "<span class=\"person\">Müller</span>",
+                "<span class=\"comment\" style=\"display: none\"> Bitte Laptop mitbringen </span>",
+                "<span class=\"tooltip\"><span class=\"comment\">Raum&nbsp;geändert</span></span>",
+            ),
+        );
+        let calendar = parse_calendar(&html, 2023).unwrap();
+
+        let event = &calendar.events[0];
+        assert_eq!(
+            event.notes.as_deref(),
+            Some("Bitte Laptop mitbringen\nRaum\u{a0}geändert")
+        );
+        assert_eq!(
+            event.description.as_deref(),
+            Some("T3INF1001\nA 1.01, B 2.02")
+        );
+    }
+
+    #[test]
+    fn test_parse_calendar_entities() {
+        let html = CALENDAR
+            .replace(" TINF22B ", "TINF22B &amp; TINF22A")
+            .replace(
+                "Mathematik &amp; Statistik",
+                "&lt;Mathe&gt; &#8211; &quot;Statistik&quot;",
+            )
+            .replace("A 1.01", "A&#160;1.01");
+        let calendar = parse_calendar(&html, 2023).unwrap();
+        assert_eq!(calendar.name, "TINF22B & TINF22A");
+
+        let event = &calendar.events[0];
+        assert_eq!(event.title, "<Mathe> \u{2013} \"Statistik\"");
+        assert_eq!(event.locations[0], "A\u{a0}1.01");
+    }
+
+    #[test]
+    fn test_parse_calendar_malformed() {
+        assert!(matches!(
+            parse_calendar("", 2023),
+            Err(ParseError::MissingTitle)
+        ));
+        assert!(matches!(
+            Calendar::try_from(""),
+            Err(ParseError::MissingStartYear)
+        ));
+
+        let no_table = "<html><head><title>TINF22B</title></head><body></body></html>";
+        assert!(parse_calendar(no_table, 2023).unwrap().events.is_empty());
+        assert!(matches!(
+            Calendar::try_from(no_table),
+            Err(ParseError::MissingStartYear)
+        ));
+
+        let unclassified = CALENDAR.replace("<tr>\n<td class=\"week_times\">", "<tr><td>");
+        assert!(matches!(
+            parse_calendar(&unclassified, 2023),
+            Err(ParseError::MissingColumnClass)
+        ));
+
+        // Without their anchors events have no details, they are skipped rather than failing the page.
+        let no_links = CALENDAR
+            .replace(
+                "<a href=\"https://rapla.dhbw.de/rapla/event?id=1&amp;key=abc\">",
+                "",
+            )
+            .replace("<a href=\"javascript:void(0)\">", "")
+            .replace("</a>", "");
+        assert!(!no_links.contains("<a"));
+        let (calendar, warnings) = parse_calendar_lossy(&no_links, 2023).unwrap();
+        assert!(calendar.events.is_empty());
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings
+            .iter()
+            .all(|warning| matches!(warning.error, ParseError::MissingEventDetails)));
+
+        // A week number that doesn't match the date, followed by a week without any rows.
+        let empty_week = CALENDAR.replace("KW 43", "KW 1").replace(
+            "</tbody></table>",
+            concat!(
+                "</tbody></table><table class=\"week_table\"><tbody>",
+                "<tr><th class=\"week_number\">KW 1</th><td class=\"week_header\"><nobr>Mo 31.12.</nobr></td></tr>",
+                "</tbody></table>"
+            ),
+        );
+        assert_eq!(parse_calendar(&empty_week, 2023).unwrap().events.len(), 2);
+
+        assert!(matches!(
+            parse_calendar(CALENDAR, i32::MAX),
+            Err(ParseError::MalformedDate { .. })
+        ));
+        assert!(matches!(
+            Calendar::try_from(&b"<title>\xff</title>"[..]),
+            Err(ParseError::InvalidUtf8(_))
+        ));
+    }
+
+    #[test]
+    fn test_all_from_html() {
+        let start = CALENDAR.find("<table").unwrap();
+        let end = CALENDAR.find("</div>").unwrap();
+        let week = &CALENDAR[start..end];
+
+        // The first resource crosses into the next year, the second one starts over.
+        let first = format!(
+            "{}{}",
+            week.replace("KW 43", "KW 52").replace("23.10.", "25.12."),
+            week.replace("KW 43", "KW 1").replace("23.10.", "01.01.")
+        );
+        let html = CALENDAR.replace(
+            week,
+            &format!("{first}</div><div class=\"calendar\">{week}"),
+        );
+
+        let calendars = Calendar::all_from_html(&html).unwrap();
+        assert_eq!(calendars.len(), 2);
+        assert_eq!(calendars[0].events.len(), 4);
+        assert_eq!(
+            calendars[0].events[2].date,
+            NaiveDate::from_ymd_opt(2024, 1, 1).unwrap()
+        );
+        assert_eq!(
+            calendars[1].events[0].date,
+            NaiveDate::from_ymd_opt(2023, 10, 23).unwrap()
+        );
+
+        let merged = parse_calendar(&html, 2023).unwrap();
+        assert_eq!(merged.events[4..], calendars[1].events);
+    }
+
+    /// A page with the given weeks of the fixture, each as week number and date of its monday.
+    fn weeks_page(weeks: &[(u32, &str)]) -> String {
+        let start = CALENDAR.find("<table").unwrap();
+        let end = CALENDAR.find("</div>").unwrap();
+        let week = &CALENDAR[start..end];
+
+        let weeks = weeks
+            .iter()
+            .map(|(week_number, monday)| {
+                week.replace("KW 43", &format!("KW {week_number}"))
+                    .replace("23.10.", monday)
+            })
+            .collect::<String>();
+        CALENDAR.replace(week, &weeks)
+    }
+
+    fn mondays(html: &str, start_year: i32) -> Vec<NaiveDate> {
+        parse_calendar(html, start_year)
+            .unwrap()
+            .events
+            .into_iter()
+            .step_by(2)
+            .map(|event| event.date)
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_calendar_year_transitions() {
+        let date = |year, month, day| NaiveDate::from_ymd_opt(year, month, day).unwrap();
+
+        // Week 1 of 2025 starts on the 30th of december.
+        let html = weeks_page(&[(52, "23.12."), (1, "30.12."), (2, "06.01.")]);
+        assert_eq!(
+            mondays(&html, 2024),
+            [date(2024, 12, 23), date(2024, 12, 30), date(2025, 1, 6)]
+        );
+
+        // The year dropdown shows 2025 if a date in january of that first week was picked.
+        let html = weeks_page(&[(1, "30.12."), (2, "06.01.")]);
+        assert_eq!(mondays(&html, 2025), [date(2024, 12, 30), date(2025, 1, 6)]);
+        assert_eq!(mondays(&html, 2024), [date(2024, 12, 30), date(2025, 1, 6)]);
+
+        // Weeks without events may be left out, including week 1.
+        let html = weeks_page(&[(51, "18.12."), (2, "08.01."), (3, "15.01.")]);
+        assert_eq!(
+            mondays(&html, 2023),
+            [date(2023, 12, 18), date(2024, 1, 8), date(2024, 1, 15)]
+        );
+
+        // Long views may cross more than one new year.
+        let html = weeks_page(&[
+            (52, "25.12."),
+            (1, "01.01."),
+            (52, "23.12."),
+            (1, "30.12."),
+            (2, "06.01."),
+        ]);
+        assert_eq!(
+            mondays(&html, 2023),
+            [
+                date(2023, 12, 25),
+                date(2024, 1, 1),
+                date(2024, 12, 23),
+                date(2024, 12, 30),
+                date(2025, 1, 6)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_calendar_variants() {
+        let (_, context) = parse_calendar_with_context(CALENDAR, 2023).unwrap();
+        assert_eq!(context.variant, RaplaVariant::OldFormat);
+
+        let html = CALENDAR
+            .replace("<title> TINF22B </title>", "<title></title>")
+            .replace("<body>", "<body><h2>TINF22B</h2>")
+            .replace("</select></form>", "</select><input name=\"goto\"></form>")
+            .replace("<nobr>Mo 23.10.</nobr>", "Mo 23.10.");
+        let (calendar, context) = parse_calendar_with_context(&html, 2023).unwrap();
+        assert_eq!(context.variant, RaplaVariant::NewFormat);
+        assert_eq!(calendar, parse_calendar(CALENDAR, 2023).unwrap());
+
+        let selectors = Selectors {
+            goto_form: Selector::parse("form [name=year]").unwrap(),
+            ..Selectors::default()
+        };
+        let (_, context) = parse_calendar_with_selectors(CALENDAR, 2023, &selectors).unwrap();
+        assert_eq!(context.variant, RaplaVariant::NewFormat);
+    }
+
+    #[test]
+    fn test_parse_calendar_last_day() {
+        let html = CALENDAR.replace(
+            "<nobr>Mo 23.10.</nobr></td>",
+            "<nobr>Mo 23.10.</nobr></td><td class=\"week_header\"><nobr>Di 24.10.</nobr></td>",
+        );
+        assert_eq!(parse_calendar(&html, 2023).unwrap().events.len(), 2);
+
+        let html = CALENDAR.replace("Mo 23.10.", "Mo 23.10. \u{2013} 23.10.");
+        let (calendar, warnings) = parse_calendar_lossy(&html, 2023).unwrap();
+        assert_eq!(calendar.events.len(), 1);
+        assert!(matches!(
+            warnings[0].error,
+            ParseError::DateOutOfRange { .. }
+        ));
+    }
+
+    #[test]
+    fn test_parse_calendar_lossy() {
+        let html = CALENDAR.replace("08:30&nbsp;-10:00", "8.30&nbsp;-10:00");
+        let (calendar, warnings) = parse_calendar_lossy(&html, 2023).unwrap();
+        assert_eq!(calendar.events.len(), 1);
+        assert_eq!(warnings.len(), 1);
+        assert!(matches!(
+            warnings[0].error,
+            ParseError::MalformedTime { .. }
+        ));
+    }
+
+    #[test]
+    fn test_calendar_try_from() {
+        let calendar = Calendar::try_from(CALENDAR).unwrap();
+        assert_eq!(calendar, parse_calendar(CALENDAR, 2023).unwrap());
+        assert!(Calendar::try_from(&b"\xff"[..]).is_err());
+        assert_eq!(
+            Calendar::from_reader(CALENDAR.as_bytes()).unwrap(),
+            calendar
+        );
+    }
+
+    #[test]
+    fn test_parse_calendar_week_number_formats() {
+        for week_number in ["KW 43", "KW&nbsp;43", " 43 "] {
+            let html = CALENDAR.replace("KW 43", week_number);
+            assert_eq!(parse_calendar(&html, 2023).unwrap().events.len(), 2);
+        }
+
+        let html = CALENDAR.replace("KW 43", "KW");
+        assert!(matches!(
+            parse_calendar(&html, 2023),
+            Err(ParseError::MalformedWeekNumber { .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_calendar_week_header_nbsp() {
+        for header in ["Mo&nbsp;23.10.", "Mo\u{a0}23.10.", "Mo  23.10."] {
+            let html = CALENDAR.replace("Mo 23.10.", header);
+            assert_eq!(
+                parse_calendar(&html, 2023).unwrap(),
+                parse_calendar(CALENDAR, 2023).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_from_html_with_selectors() {
+        let html = CALENDAR
+            .replace("div class=\"calendar\"", "div class=\"kalender\"")
+            .replace("span class=\"resource\"", "span class=\"raum\"");
+        assert!(Calendar::try_from(html.as_str()).unwrap().events.is_empty());
+
+        let selectors = Selectors {
+            calendar: Selector::parse("div.kalender").unwrap(),
+            event_resource: Selector::parse("span.raum").unwrap(),
+            ..Selectors::default()
+        };
+        assert_eq!(
+            Calendar::from_html_with_selectors(&html, &selectors).unwrap(),
+            Calendar::try_from(CALENDAR).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_from_html_with_config() {
+        let config = ParserConfig::default();
+        assert_eq!(
+            Calendar::from_html_with_config(CALENDAR, &config).unwrap(),
+            Calendar::try_from(CALENDAR).unwrap()
+        );
+
+        let html = CALENDAR.replace("08:30&nbsp;-10:00", "08:30&nbsp;-25:00");
+        assert_eq!(
+            Calendar::from_html_with_config(&html, &config)
+                .unwrap()
+                .events
+                .len(),
+            1
+        );
+        assert!(matches!(
+            Calendar::from_html_with_config(&html, &config.clone().strict(true)),
+            Err(ParseError::MalformedTime { .. })
+        ));
+
+        let html = CALENDAR.replace("<option selected>2023</option>", "");
+        assert!(Calendar::from_html_with_config(&html, &config).is_ok());
+        assert!(matches!(
+            Calendar::from_html_with_config(&html, &config.strict(true)),
+            Err(ParseError::MissingStartYear)
+        ));
+    }
+
+    #[test]
+    fn test_parse_calendar_missing_title() {
+        // Events alone don't make up for a missing name.
+        let html = CALENDAR.replace("<head><title> TINF22B </title></head>", "");
+        assert!(matches!(
+            parse_calendar(&html, 2023),
+            Err(ParseError::MissingTitle)
+        ));
+    }
+}
@@ -7,21 +7,23 @@ use axum::routing::get;
 use axum::{Extension, Router};
 
 use crate::calendar::Calendar;
+use crate::parser::ParseError;
 use crate::resolver::UpstreamUrlExtension;
 
 pub enum Error {
     Request(reqwest::Error),
-    Parse,
+    Parse(ParseError),
 }
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let message = match &self {
-            Self::Request(err) if err.is_status() => "upstream returned unexpected status code",
-            Self::Request(_) => "can't connect to upstream",
-            Self::Parse => "can't parse calendar",
-        };
-        write!(f, "{message}")
+        match &self {
+            Self::Request(err) if err.is_status() => {
+                write!(f, "upstream returned unexpected status code")
+            }
+            Self::Request(_) => write!(f, "can't connect to upstream"),
+            Self::Parse(err) => write!(f, "can't parse calendar: {err}"),
+        }
     }
 }
 
@@ -35,7 +37,7 @@ impl std::error::Error for Error {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
             Self::Request(err) => Some(err),
-            Self::Parse => None,
+            Self::Parse(err) => Some(err),
         }
     }
 }
@@ -46,6 +48,12 @@ impl From<reqwest::Error> for Error {
     }
 }
 
+impl From<ParseError> for Error {
+    fn from(value: ParseError) -> Self {
+        Self::Parse(value)
+    }
+}
+
 impl IntoResponse for Error {
     fn into_response(self) -> Response {
         let status = match &self {
@@ -53,7 +61,7 @@ impl IntoResponse for Error {
                 err.status().expect("error status should be set")
             } // Propagate whatever issue they're having.
             Self::Request(_) => StatusCode::BAD_GATEWAY,
-            Self::Parse => StatusCode::INTERNAL_SERVER_ERROR,
+            Self::Parse(_) => StatusCode::INTERNAL_SERVER_ERROR,
         };
 
         (
@@ -67,11 +75,7 @@ impl IntoResponse for Error {
 
 impl IntoResponse for Calendar {
     fn into_response(self) -> axum::response::Response {
-        (
-            [("content-type", "text/calendar")],
-            self.to_ics().to_string(),
-        )
-            .into_response()
+        ([("content-type", "text/calendar")], self.to_ics_string()).into_response()
     }
 }
 
@@ -101,5 +105,5 @@ pub async fn handle(
     let request = client.get(&upstream.url).build()?;
     let response = client.execute(request).await?.error_for_status()?;
     let html = response.text().await?;
-    crate::parser::parse_calendar(&html, upstream.start_year).ok_or(Error::Parse)
+    Ok(crate::parser::parse_calendar(&html, upstream.start_year)?)
 }
@@ -0,0 +1,188 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use chrono::{Datelike, Duration, NaiveTime, Offset};
+use ics::parameters::TzIDParam;
+use ics::properties::{ExDate, RRule};
+
+use crate::calendar::{Calendar, Event, ICSOptions};
+use crate::timezone::localize;
+
+/// Occurrences of a series may be up to this many weeks apart, e.g. around the holidays.
+/// Longer breaks start a new series.
+const MAX_WEEKS_BETWEEN: i64 = 3;
+
+/// Whether the event can be an occurrence of the series started by `first`, apart from its date.
+fn same_series(first: &Event, event: &Event, opts: &ICSOptions) -> bool {
+    // In UTC the series would keep its time across a switch to or from daylight saving time,
+    // while Rapla keeps the local time.
+    let offset = |event: &Event| {
+        localize(event.start_datetime(), opts.timezone)
+            .offset()
+            .fix()
+    };
+
+    first.end_date - first.date == event.end_date - event.date
+        && first.organizer == event.organizer
+        && first.description == event.description
+        && first.notes == event.notes
+        && first.category == event.category
+        && first.rapla_url == event.rapla_url
+        && first.sequence == event.sequence
+        && (!opts.utc || offset(first) == offset(event))
+}
+
+/// Groups events into weekly series, each given as the indices of its events in chronological
+/// order. Events that don't repeat end up in a series of their own.
+fn weekly_series(events: &[Event], opts: &ICSOptions) -> Vec<Vec<usize>> {
+    fn key(event: &Event) -> (u32, NaiveTime, NaiveTime, &str, &[String]) {
+        (
+            event.date.weekday().num_days_from_monday(),
+            event.start,
+            event.end,
+            &event.title,
+            &event.locations,
+        )
+    }
+
+    let mut groups = BTreeMap::<_, Vec<usize>>::new();
+    for (idx, event) in events.iter().enumerate() {
+        groups.entry(key(event)).or_default().push(idx);
+    }
+
+    let mut series: Vec<Vec<usize>> = Vec::new();
+    for mut group in groups.into_values() {
+        group.sort_by_key(|&idx| events[idx].date);
+
+        let group_start = series.len();
+        for idx in group {
+            let event = &events[idx];
+            let open = series[group_start..].iter_mut().find(|series| {
+                let last = &events[*series.last().expect("series are never empty")];
+                (1..=MAX_WEEKS_BETWEEN).contains(&(event.date - last.date).num_weeks())
+                    && same_series(&events[series[0]], event, opts)
+            });
+
+            match open {
+                Some(open) => open.push(idx),
+                None => series.push(vec![idx]),
+            }
+        }
+    }
+
+    // Keep the order of the calendar, as far as possible.
+    series.sort_by_key(|series| series.iter().min().copied());
+    series
+}
+
+impl Calendar {
+    /// Like [`Calendar::to_ics`], but events repeating weekly at the same time and place are
+    /// emitted once with an `RRULE`. Weeks that a series skips are listed in its `EXDATE`.
+    #[must_use]
+    pub fn to_ics_recurring(&self) -> ics::ICalendar<'_> {
+        self.to_ics_recurring_with_options(ICSOptions::default())
+    }
+
+    #[must_use]
+    pub fn to_ics_recurring_with_options(&self, mut opts: ICSOptions) -> ics::ICalendar<'_> {
+        let mut icalendar = self.ics_calendar(&mut opts);
+
+        for series in weekly_series(&self.events, &opts) {
+            let first = &self.events[series[0]];
+            let mut ics_event = first.to_ics_with_options(&opts);
+
+            if let [_, .., last] = series[..] {
+                let count = (self.events[last].date - first.date).num_weeks() + 1;
+                ics_event.push(RRule::new(format!("FREQ=WEEKLY;COUNT={count}")));
+
+                let dates = series
+                    .iter()
+                    .map(|&idx| self.events[idx].date)
+                    .collect::<BTreeSet<_>>();
+                let skipped = (1..count)
+                    .map(|week| first.start_datetime() + Duration::weeks(week))
+                    .filter(|start| !dates.contains(&start.date()))
+                    .map(|start| {
+                        if opts.utc {
+                            Event::format_utc(start, opts.timezone)
+                        } else {
+                            Event::format_local(start)
+                        }
+                    })
+                    .collect::<Vec<_>>();
+
+                if !skipped.is_empty() {
+                    let mut exdate = ExDate::new(skipped.join(","));
+                    if !opts.utc {
+                        exdate.add(TzIDParam::new(opts.timezone.name()));
+                    }
+                    ics_event.push(exdate);
+                }
+            }
+
+            icalendar.add_event(ics_event);
+        }
+
+        icalendar
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, NaiveDate, NaiveTime};
+
+    use crate::builder::EventBuilder;
+    use crate::calendar::{Calendar, Event, ICSOptions};
+
+    fn event(date: NaiveDate, title: &str) -> Event {
+        EventBuilder::new(
+            date,
+            NaiveTime::from_hms_opt(10, 0, 0).unwrap(),
+            NaiveTime::from_hms_opt(12, 0, 0).unwrap(),
+            title,
+        )
+        .location("HS 101")
+        .build()
+    }
+
+    #[test]
+    fn test_to_ics_recurring() {
+        let monday = NaiveDate::from_ymd_opt(2023, 10, 2).unwrap();
+        let mut events = (0..14)
+            .filter(|week| *week != 5)
+            .map(|week| event(monday + Duration::weeks(week), "Mathematik 1"))
+            .collect::<Vec<_>>();
+        events.push(event(monday + Duration::days(1), "Programmieren"));
+        let calendar = Calendar {
+            name: "TINF22B".to_string(),
+            events,
+        };
+
+        let ics = calendar.to_ics_recurring().to_string();
+        assert_eq!(ics.matches("BEGIN:VEVENT").count(), 2);
+        assert_eq!(ics.matches("RRULE:FREQ=WEEKLY").count(), 1);
+        assert!(ics.contains("DTSTART;TZID=Europe/Berlin:20231002T100000\r\n"));
+        assert!(ics.contains("RRULE:FREQ=WEEKLY;COUNT=14\r\n"));
+        assert!(ics.contains("EXDATE;TZID=Europe/Berlin:20231106T100000\r\n"));
+    }
+
+    #[test]
+    fn test_to_ics_recurring_utc_splits_at_dst() {
+        // Daylight saving time ends on the 29th of October 2023.
+        let monday = NaiveDate::from_ymd_opt(2023, 10, 16).unwrap();
+        let calendar = Calendar {
+            name: "TINF22B".to_string(),
+            events: (0..4)
+                .map(|week| event(monday + Duration::weeks(week), "Mathematik 1"))
+                .collect(),
+        };
+
+        let opts = ICSOptions {
+            utc: true,
+            ..ICSOptions::default()
+        };
+        let ics = calendar.to_ics_recurring_with_options(opts).to_string();
+        assert_eq!(ics.matches("RRULE:FREQ=WEEKLY;COUNT=2\r\n").count(), 2);
+        assert!(ics.contains("DTSTART:20231016T080000Z\r\n"));
+        assert!(ics.contains("DTSTART:20231030T090000Z\r\n"));
+    }
+}
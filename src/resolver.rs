@@ -6,27 +6,14 @@ use axum::middleware::{self, Next};
 use axum::response::{IntoResponse, Response};
 use axum::Router;
 use chrono::{Datelike, Duration, Utc};
-use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(untagged)]
-enum RaplaBaseQuery {
-    V1 { key: String, salt: String },
-    V2 { user: String, file: String },
-}
-
-#[derive(Debug, Clone, Deserialize)]
-struct RaplaQueryWithPage {
-    #[serde(flatten)]
-    base: RaplaBaseQuery,
-    page: Option<String>,
-}
+use crate::url::RaplaUrl;
 
 #[derive(Debug, Clone)]
 pub struct UpstreamUrlComponents {
     host: String,
     page: String,
-    query: RaplaBaseQuery,
+    url: RaplaUrl,
 }
 
 #[derive(Debug, Clone)]
@@ -80,8 +67,8 @@ impl UpstreamUrlComponents {
             return None;
         }
 
-        let query: RaplaQueryWithPage = serde_urlencoded::from_str(uri.query()?).ok()?;
-        let page = query.page.or_else(|| {
+        let url = RaplaUrl::from_uri(uri).ok()?;
+        let page = url.page.clone().or_else(|| {
             let path = uri.path();
             path.starts_with("/rapla/")
                 .then(|| path.trim_start_matches("/rapla/").to_string())
@@ -90,32 +77,60 @@ impl UpstreamUrlComponents {
         Some(UpstreamUrlComponents {
             host: host.to_string(),
             page,
-            query: query.base,
+            url,
         })
     }
 
     pub fn generate_url(self) -> UpstreamUrlExtension {
         // These don't need to be 100% accurate.
-        const WEEKS_TWO_YEARS: usize = 104;
+        const WEEKS_TWO_YEARS: u32 = 104;
         const DAYS_ONE_YEAR: i64 = 365;
 
         let now = Utc::now();
         let year_ago = now - Duration::try_days(DAYS_ONE_YEAR).unwrap();
 
-        let url = format!(
-            "https://{}/rapla/{}?day={}&month={}&year={}&pages={WEEKS_TWO_YEARS}&{}",
-            self.host,
-            self.page,
-            year_ago.day(),
-            year_ago.month(),
-            year_ago.year(),
-            // There's no reason this should fail, we already parsed it in the first place.
-            serde_urlencoded::to_string(self.query).unwrap()
-        );
+        let url = RaplaUrl {
+            base_url: format!("https://{}/rapla/{}", self.host, self.page),
+            page: None,
+            day: Some(year_ago.day()),
+            month: Some(year_ago.month()),
+            year: Some(year_ago.year()),
+            goto: None,
+            pages: Some(WEEKS_TWO_YEARS),
+            ..self.url
+        };
 
         UpstreamUrlExtension {
-            url,
+            url: url.to_url(),
             start_year: year_ago.year(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use axum::http::Uri;
+
+    use super::UpstreamUrlComponents;
+
+    fn resolve(uri: &'static str) -> Option<String> {
+        UpstreamUrlComponents::from_request_uri(&Uri::from_static(uri))
+            .map(|components| components.generate_url().url)
+    }
+
+    #[test]
+    fn test_resolve() {
+        let url = resolve("/rapla/calendar?key=abc&salt=def").unwrap();
+        assert!(url.starts_with("https://rapla.dhbw.de/rapla/calendar?key=abc&salt=def&day="));
+        assert!(url.ends_with("&pages=104"));
+
+        let url = resolve("/https://rapla.dhbw.de/rapla?page=calendar&user=mueller&file=TINF22B")
+            .unwrap();
+        assert!(
+            url.starts_with("https://rapla.dhbw.de/rapla/calendar?user=mueller&file=TINF22B&day=")
+        );
+
+        assert_eq!(resolve("/https://example.com/rapla/calendar?key=abc"), None);
+        assert_eq!(resolve("/rapla/calendar?salt=def"), None);
+    }
+}
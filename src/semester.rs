@@ -0,0 +1,92 @@
+use std::collections::HashMap;
+
+use chrono::{Datelike, NaiveDate};
+
+use crate::calendar::Calendar;
+
+/// Semesters as used at German universities, the winter semester spans the turn of the year.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Semester {
+    /// April to September.
+    Summer(i32),
+    /// October to March, with the years it starts and ends in.
+    Winter(i32, i32),
+}
+
+impl Semester {
+    #[must_use]
+    pub fn of(date: NaiveDate) -> Semester {
+        match date.month() {
+            4..=9 => Semester::Summer(date.year()),
+            10..=12 => Semester::Winter(date.year(), date.year() + 1),
+            _ => Semester::Winter(date.year() - 1, date.year()),
+        }
+    }
+}
+
+impl Calendar {
+    /// ISO week numbers that have at least one event, in chronological order.
+    #[must_use]
+    pub fn academic_weeks(&self) -> Vec<u32> {
+        self.group_by_week()
+            .into_keys()
+            .map(|(_, week)| week)
+            .collect()
+    }
+
+    /// The semester most events fall into, `None` for an empty calendar.
+    #[must_use]
+    pub fn semester(&self) -> Option<Semester> {
+        let mut counts = HashMap::<_, usize>::new();
+        for event in &self.events {
+            *counts.entry(Semester::of(event.date)).or_default() += 1;
+        }
+
+        counts
+            .into_iter()
+            .max_by_key(|&(semester, count)| (count, semester_start(semester)))
+            .map(|(semester, _)| semester)
+    }
+}
+
+/// Breaks ties between semesters with the same number of events in favor of the later one.
+fn semester_start(semester: Semester) -> (i32, u32) {
+    match semester {
+        Semester::Summer(year) => (year, 4),
+        Semester::Winter(year, _) => (year, 10),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, NaiveTime};
+
+    use super::Semester;
+    use crate::builder::{CalendarBuilder, EventBuilder};
+
+    #[test]
+    fn test_semester() {
+        let event = |year, month, day| {
+            EventBuilder::new(
+                NaiveDate::from_ymd_opt(year, month, day).unwrap(),
+                NaiveTime::from_hms_opt(8, 0, 0).unwrap(),
+                NaiveTime::from_hms_opt(10, 0, 0).unwrap(),
+                "A",
+            )
+            .build()
+        };
+
+        let calendar = CalendarBuilder::new("TINF22B")
+            .extend_events([
+                event(2023, 9, 25),
+                event(2023, 10, 23),
+                event(2023, 10, 24),
+                event(2024, 1, 2),
+            ])
+            .build()
+            .unwrap();
+
+        assert_eq!(calendar.academic_weeks(), [39, 43, 1]);
+        assert_eq!(calendar.semester(), Some(Semester::Winter(2023, 2024)));
+    }
+}
@@ -0,0 +1,200 @@
+use std::fmt;
+
+use reqwest::header;
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+
+use crate::calendar::Event;
+use crate::diff::CalendarDiff;
+
+const USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+
+/// Slack rejects sections longer than 3000 characters, longer lists are cut off well before that.
+const MAX_EVENTS_PER_SECTION: usize = 20;
+
+#[derive(Debug)]
+pub enum SlackError {
+    Request(reqwest::Error),
+    Status(StatusCode),
+}
+
+impl fmt::Display for SlackError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Request(err) => write!(f, "request failed: {err}"),
+            Self::Status(status) => write!(f, "slack returned unexpected status code {status}"),
+        }
+    }
+}
+
+impl std::error::Error for SlackError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Request(err) => Some(err),
+            Self::Status(_) => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for SlackError {
+    fn from(value: reqwest::Error) -> Self {
+        Self::Request(value)
+    }
+}
+
+/// Posts calendar changes to a Slack incoming webhook.
+#[derive(Debug)]
+pub struct SlackNotifier {
+    webhook_url: String,
+    client: reqwest::blocking::Client,
+}
+
+/// Slack only needs these three escaped, everything else in mrkdwn is shown as is.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn section(heading: &str, lines: Vec<String>) -> Value {
+    let mut text = format!("*{heading}*");
+    for line in lines.iter().take(MAX_EVENTS_PER_SECTION) {
+        text += "\n\u{2022} ";
+        text += &escape(line);
+    }
+    if lines.len() > MAX_EVENTS_PER_SECTION {
+        text += &format!("\n_and {} more_", lines.len() - MAX_EVENTS_PER_SECTION);
+    }
+
+    json!({ "type": "section", "text": { "type": "mrkdwn", "text": text } })
+}
+
+/// Block Kit message with a section for each kind of change that happened.
+fn payload(diff: &CalendarDiff) -> Value {
+    let summary = format!(
+        "Calendar changed: {} added, {} removed, {} changed",
+        diff.added.len(),
+        diff.removed.len(),
+        diff.changed.len()
+    );
+
+    let mut blocks = vec![json!({
+        "type": "header",
+        "text": { "type": "plain_text", "text": "Calendar changed" },
+    })];
+
+    let events = |events: &[Event]| events.iter().map(Event::to_string).collect::<Vec<_>>();
+    if !diff.added.is_empty() {
+        blocks.push(section("Added", events(&diff.added)));
+    }
+    if !diff.removed.is_empty() {
+        blocks.push(section("Removed", events(&diff.removed)));
+    }
+    if !diff.changed.is_empty() {
+        let changes = diff
+            .changed
+            .iter()
+            .map(|(old, new)| format!("{old} \u{2192} {new}"))
+            .collect();
+        blocks.push(section("Changed", changes));
+    }
+
+    json!({ "text": summary, "blocks": blocks })
+}
+
+impl SlackNotifier {
+    pub fn new(webhook_url: &str) -> Result<Self, SlackError> {
+        Ok(Self {
+            webhook_url: webhook_url.to_string(),
+            client: reqwest::blocking::Client::builder()
+                .user_agent(USER_AGENT)
+                .build()?,
+        })
+    }
+
+    /// Sends a message listing added, removed and changed events. Nothing is sent for an empty diff.
+    pub fn notify_diff(&self, diff: &CalendarDiff) -> Result<(), SlackError> {
+        if diff.is_empty() {
+            return Ok(());
+        }
+
+        let response = self
+            .client
+            .post(&self.webhook_url)
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(payload(diff).to_string())
+            .send()?;
+        if !response.status().is_success() {
+            return Err(SlackError::Status(response.status()));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, NaiveTime};
+
+    use super::{payload, MAX_EVENTS_PER_SECTION};
+    use crate::builder::EventBuilder;
+    use crate::calendar::Event;
+    use crate::diff::CalendarDiff;
+
+    fn event(title: &str) -> Event {
+        EventBuilder::new(
+            NaiveDate::from_ymd_opt(2023, 10, 23).unwrap(),
+            NaiveTime::from_hms_opt(8, 0, 0).unwrap(),
+            NaiveTime::from_hms_opt(10, 0, 0).unwrap(),
+            title,
+        )
+        .build()
+    }
+
+    #[test]
+    fn test_payload() {
+        let mut moved = event("Mathematik <1>");
+        moved.locations.push("HS 101".to_string());
+        let diff = CalendarDiff {
+            added: vec![event("Programmieren")],
+            removed: Vec::new(),
+            changed: vec![(event("Mathematik <1>"), moved)],
+        };
+
+        let payload = payload(&diff);
+        assert_eq!(
+            payload["text"],
+            "Calendar changed: 1 added, 0 removed, 1 changed"
+        );
+
+        let blocks = payload["blocks"].as_array().unwrap();
+        assert_eq!(blocks.len(), 3);
+        assert_eq!(blocks[0]["type"], "header");
+        assert_eq!(
+            blocks[1]["text"]["text"],
+            "*Added*\n\u{2022} 2023-10-23 08:00\u{2013}10:00  Programmieren"
+        );
+        assert_eq!(
+            blocks[2]["text"]["text"],
+            concat!(
+                "*Changed*\n\u{2022} 2023-10-23 08:00\u{2013}10:00  Mathematik &lt;1&gt; \u{2192} ",
+                "2023-10-23 08:00\u{2013}10:00  Mathematik &lt;1&gt;  (HS 101)"
+            )
+        );
+    }
+
+    #[test]
+    fn test_payload_truncates_long_sections() {
+        let diff = CalendarDiff {
+            added: vec![event("Programmieren"); MAX_EVENTS_PER_SECTION + 5],
+            ..CalendarDiff::default()
+        };
+
+        let text = payload(&diff)["blocks"][1]["text"]["text"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        assert_eq!(text.matches('\u{2022}').count(), MAX_EVENTS_PER_SECTION);
+        assert!(text.ends_with("\n_and 5 more_"));
+    }
+}
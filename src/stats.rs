@@ -0,0 +1,100 @@
+use chrono::{Duration, NaiveDate};
+
+use crate::calendar::Calendar;
+
+/// Summary of a calendar, see [`Calendar::stats`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CalendarStats {
+    pub event_count: usize,
+    /// The day the first event starts and the day the last one ends.
+    pub date_range: Option<(NaiveDate, NaiveDate)>,
+    pub unique_locations: usize,
+    pub unique_titles: usize,
+    pub total_duration: Duration,
+    /// Zero for an empty calendar.
+    pub average_event_duration: Duration,
+    /// See [`Calendar::busiest_day`].
+    pub busiest_day: Option<(NaiveDate, usize)>,
+    /// Number of ISO weeks with at least one event.
+    pub weeks_covered: usize,
+}
+
+impl Calendar {
+    #[must_use]
+    pub fn stats(&self) -> CalendarStats {
+        let total_duration = self.total_duration();
+        let average_event_duration = match i32::try_from(self.events.len()) {
+            Ok(0) | Err(_) => Duration::zero(),
+            Ok(count) => total_duration / count,
+        };
+
+        let first = self.events.iter().map(|event| event.date).min();
+        let last = self.events.iter().map(|event| event.end_date).max();
+
+        CalendarStats {
+            event_count: self.events.len(),
+            date_range: first.zip(last),
+            unique_locations: self.locations().len(),
+            unique_titles: self.unique_titles().len(),
+            total_duration,
+            average_event_duration,
+            busiest_day: self.busiest_day(),
+            weeks_covered: self.group_by_week().len(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, NaiveDate, NaiveTime};
+
+    use crate::builder::{CalendarBuilder, EventBuilder};
+    use crate::calendar::Calendar;
+
+    #[test]
+    fn test_stats() {
+        let event = |day, start, end, title, location| {
+            EventBuilder::new(
+                NaiveDate::from_ymd_opt(2023, 10, day).unwrap(),
+                NaiveTime::from_hms_opt(start, 0, 0).unwrap(),
+                NaiveTime::from_hms_opt(end, 0, 0).unwrap(),
+                title,
+            )
+            .location(location)
+            .build()
+        };
+
+        let calendar = CalendarBuilder::new("TINF22B")
+            .extend_events([
+                event(24, 8, 10, "Mathematik 1", "HS 101"),
+                event(24, 10, 12, "Programmieren", "HS 101"),
+                event(30, 8, 12, "Mathematik 1", "A 1.01"),
+                event(31, 22, 1, "Labor", "B 2.02"),
+            ])
+            .build()
+            .unwrap();
+
+        let stats = calendar.stats();
+        assert_eq!(stats.event_count, 4);
+        assert_eq!(
+            stats.date_range,
+            Some((
+                NaiveDate::from_ymd_opt(2023, 10, 24).unwrap(),
+                NaiveDate::from_ymd_opt(2023, 11, 1).unwrap()
+            ))
+        );
+        assert_eq!(stats.unique_locations, 3);
+        assert_eq!(stats.unique_titles, 3);
+        assert_eq!(stats.total_duration, Duration::hours(11));
+        assert_eq!(stats.average_event_duration, Duration::minutes(165));
+        assert_eq!(
+            stats.busiest_day,
+            Some((NaiveDate::from_ymd_opt(2023, 10, 24).unwrap(), 2))
+        );
+        assert_eq!(stats.weeks_covered, 2);
+
+        let stats = Calendar::default().stats();
+        assert_eq!(stats.date_range, None);
+        assert_eq!(stats.average_event_duration, Duration::zero());
+    }
+}
@@ -0,0 +1,154 @@
+use std::fmt;
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+
+use crate::calendar::{Calendar, ICSOptions};
+use crate::export::JsonError;
+#[cfg(feature = "fetch")]
+use crate::fetch::FetchError;
+
+#[derive(Debug)]
+pub enum LoadError {
+    Io(io::Error),
+    Json(JsonError),
+    #[cfg(feature = "fetch")]
+    Fetch(FetchError),
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "can't read calendar file: {err}"),
+            Self::Json(err) => write!(f, "malformed calendar file: {err}"),
+            #[cfg(feature = "fetch")]
+            Self::Fetch(err) => write!(f, "can't fetch calendar: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            Self::Json(err) => Some(err),
+            #[cfg(feature = "fetch")]
+            Self::Fetch(err) => Some(err),
+        }
+    }
+}
+
+impl From<io::Error> for LoadError {
+    fn from(value: io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+impl From<JsonError> for LoadError {
+    fn from(value: JsonError) -> Self {
+        Self::Json(value)
+    }
+}
+
+#[cfg(feature = "fetch")]
+impl From<FetchError> for LoadError {
+    fn from(value: FetchError) -> Self {
+        Self::Fetch(value)
+    }
+}
+
+impl Calendar {
+    /// Stores the calendar as JSON, see [`Calendar::to_json`].
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs::write(path, self.to_json().map_err(io::Error::other)?)
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Calendar, LoadError> {
+        Ok(Self::from_json(&fs::read_to_string(path)?)?)
+    }
+
+    /// Writes the calendar as ICS, replacing the file if it exists.
+    pub fn to_ics_file<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        self.to_ics_file_with_options(path, ICSOptions::default())
+    }
+
+    pub fn to_ics_file_with_options<P: AsRef<Path>>(
+        &self,
+        path: P,
+        opts: ICSOptions,
+    ) -> io::Result<()> {
+        let mut writer = io::BufWriter::new(fs::File::create(path)?);
+        self.to_ics_with_options(opts).write(&mut writer)?;
+        writer.flush()
+    }
+
+    /// Loads the calendar from the file if it exists, otherwise fetches it and saves it there.
+    #[cfg(feature = "fetch")]
+    pub fn load_or_fetch<P: AsRef<Path>>(path: P, url: &str) -> Result<Calendar, LoadError> {
+        match Self::load(&path) {
+            Err(LoadError::Io(err)) if err.kind() == io::ErrorKind::NotFound => {
+                let calendar = Self::from_url(url)?;
+                calendar.save(&path)?;
+                Ok(calendar)
+            }
+            result => result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use chrono::{NaiveDate, NaiveTime};
+
+    use super::LoadError;
+    use crate::builder::{CalendarBuilder, EventBuilder};
+    use crate::calendar::Calendar;
+
+    #[test]
+    fn test_save_load() {
+        let event = EventBuilder::new(
+            NaiveDate::from_ymd_opt(2023, 10, 23).unwrap(),
+            NaiveTime::from_hms_opt(8, 0, 0).unwrap(),
+            NaiveTime::from_hms_opt(10, 0, 0).unwrap(),
+            "Mathematik",
+        )
+        .build();
+        let calendar = CalendarBuilder::new("TINF22B")
+            .add_event(event)
+            .build()
+            .unwrap();
+
+        let path = std::env::temp_dir().join(format!("rapla-calendar-{}.json", std::process::id()));
+        calendar.save(&path).unwrap();
+        let loaded = Calendar::load(&path);
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.unwrap(), calendar);
+        assert!(matches!(Calendar::load(&path), Err(LoadError::Io(_))));
+    }
+
+    #[test]
+    fn test_to_ics_file() {
+        let calendar = CalendarBuilder::new("TINF22B")
+            .add_event(
+                EventBuilder::new(
+                    NaiveDate::from_ymd_opt(2023, 10, 23).unwrap(),
+                    NaiveTime::from_hms_opt(8, 0, 0).unwrap(),
+                    NaiveTime::from_hms_opt(10, 0, 0).unwrap(),
+                    "Mathematik",
+                )
+                .build(),
+            )
+            .build()
+            .unwrap();
+
+        let path = std::env::temp_dir().join(format!("rapla-calendar-{}.ics", std::process::id()));
+        calendar.to_ics_file(&path).unwrap();
+        let ics = fs::read_to_string(&path);
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(Calendar::from_ics(&ics.unwrap()).unwrap(), calendar);
+    }
+}
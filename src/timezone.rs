@@ -0,0 +1,245 @@
+use chrono::{
+    DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, Offset, TimeZone as _, Utc,
+    Weekday,
+};
+use chrono_tz::{OffsetComponents, OffsetName, Tz};
+use ics::properties::{RRule, TzName};
+use ics::{Daylight, Standard, TimeZone};
+
+struct Transition {
+    /// Wall clock time at which the transition happens, in terms of the offset that is being left.
+    local: NaiveDateTime,
+    offset_from: i32,
+    offset_to: i32,
+    daylight: bool,
+    name: Option<String>,
+}
+
+fn last_day_of_month(year: i32, month: u32) -> NaiveDate {
+    let (year, month) = if month == 12 {
+        (year + 1, 1)
+    } else {
+        (year, month + 1)
+    };
+
+    NaiveDate::from_ymd_opt(year, month, 1)
+        .and_then(|date| date.pred_opt())
+        .expect("month should be valid")
+}
+
+fn format_offset(seconds: i32) -> String {
+    let sign = if seconds < 0 { '-' } else { '+' };
+    let seconds = seconds.abs();
+    format!("{sign}{:02}{:02}", seconds / 3600, seconds % 3600 / 60)
+}
+
+fn format_weekday(weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Mon => "MO",
+        Weekday::Tue => "TU",
+        Weekday::Wed => "WE",
+        Weekday::Thu => "TH",
+        Weekday::Fri => "FR",
+        Weekday::Sat => "SA",
+        Weekday::Sun => "SU",
+    }
+}
+
+/// Scan a year for offset changes. Days are checked first, then the day of a change in 15 minute steps.
+fn transitions(tz: Tz, year: i32) -> Vec<Transition> {
+    let offset_at = |time: &NaiveDateTime| tz.offset_from_utc_datetime(time).fix();
+
+    let mut transitions = Vec::new();
+    let mut day = NaiveDate::from_ymd_opt(year, 1, 1)
+        .expect("year should be valid")
+        .and_time(NaiveTime::MIN);
+
+    while day.year() == year {
+        let next_day = day + Duration::days(1);
+        if offset_at(&day) == offset_at(&next_day) {
+            day = next_day;
+            continue;
+        }
+
+        let mut time = day;
+        while offset_at(&time) == offset_at(&day) {
+            time += Duration::minutes(15);
+        }
+
+        let before = tz.offset_from_utc_datetime(&day);
+        let after = tz.offset_from_utc_datetime(&time);
+        let offset_from = before.fix().local_minus_utc();
+
+        transitions.push(Transition {
+            local: time + Duration::seconds(offset_from.into()),
+            offset_from,
+            offset_to: after.fix().local_minus_utc(),
+            daylight: after.dst_offset() != Duration::zero(),
+            name: after.abbreviation().map(str::to_string),
+        });
+
+        day = next_day;
+    }
+
+    transitions
+}
+
+/// Express the transition as a yearly recurring rule, e.g. "last sunday of october",
+/// together with its first occurrence in 1970 as is convention.
+fn recurrence(transition: &Transition) -> (String, String) {
+    let date = transition.local.date();
+    let last_day = last_day_of_month(date.year(), date.month());
+
+    let nth = if date.day() + 7 > last_day.day() {
+        -1
+    } else {
+        (date.day() as i32 - 1) / 7 + 1
+    };
+
+    let rrule = format!(
+        "FREQ=YEARLY;BYMONTH={};BYDAY={nth}{}",
+        date.month(),
+        format_weekday(date.weekday())
+    );
+
+    let onset = if nth == -1 {
+        let mut onset = last_day_of_month(1970, date.month());
+        while onset.weekday() != date.weekday() {
+            onset = onset.pred_opt().expect("date should be valid");
+        }
+        onset
+    } else {
+        NaiveDate::from_weekday_of_month_opt(1970, date.month(), date.weekday(), nth as u8)
+            .expect("every month has at least four of each weekday")
+    };
+
+    let dtstart = format!(
+        "{}T{}",
+        onset.format("%Y%m%d"),
+        transition.local.format("%H%M%S")
+    );
+
+    (dtstart, rrule)
+}
+
+/// Attach the timezone to a wall clock time. Times inside the gap of a switch to daylight saving
+/// time don't exist, they are moved past it.
+pub(crate) fn localize(local: NaiveDateTime, tz: Tz) -> DateTime<Tz> {
+    tz.from_local_datetime(&local)
+        .earliest()
+        .or_else(|| {
+            tz.from_local_datetime(&(local + Duration::hours(1)))
+                .earliest()
+        })
+        .unwrap_or_else(|| tz.from_utc_datetime(&local))
+}
+
+/// Build a VTIMEZONE definition from the transitions in the current year of the given timezone.
+/// Timezones that don't switch exactly twice per year are treated as having a fixed offset.
+pub fn build_vtimezone(tz: Tz) -> TimeZone<'static> {
+    let now = Utc::now();
+    let transitions = transitions(tz, now.year());
+
+    let mut standards = Vec::new();
+    let mut daylights = Vec::new();
+
+    if transitions.len() == 2 {
+        for transition in &transitions {
+            let (dtstart, rrule) = recurrence(transition);
+            let offset_from = format_offset(transition.offset_from);
+            let offset_to = format_offset(transition.offset_to);
+
+            if transition.daylight {
+                let mut daylight = Daylight::new(dtstart, offset_from, offset_to);
+                if let Some(name) = &transition.name {
+                    daylight.push(TzName::new(name.clone()));
+                }
+                daylight.push(RRule::new(rrule));
+                daylights.push(daylight);
+            } else {
+                let mut standard = Standard::new(dtstart, offset_from, offset_to);
+                if let Some(name) = &transition.name {
+                    standard.push(TzName::new(name.clone()));
+                }
+                standard.push(RRule::new(rrule));
+                standards.push(standard);
+            }
+        }
+    } else {
+        let offset = tz.offset_from_utc_datetime(&now.naive_utc());
+        let formatted = format_offset(offset.fix().local_minus_utc());
+
+        let mut standard = Standard::new("19700101T000000", formatted.clone(), formatted);
+        if let Some(name) = offset.abbreviation() {
+            standard.push(TzName::new(name.to_string()));
+        }
+        standards.push(standard);
+    }
+
+    let mut timezone = match standards.pop() {
+        Some(standard) => TimeZone::standard(tz.name(), standard),
+        None => TimeZone::daylight(
+            tz.name(),
+            daylights.pop().expect("at least one observance exists"),
+        ),
+    };
+
+    for standard in standards {
+        timezone.add_standard(standard);
+    }
+
+    for daylight in daylights {
+        timezone.add_daylight(daylight);
+    }
+
+    timezone
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono_tz::{America, Asia, Europe};
+
+    use super::build_vtimezone;
+
+    #[test]
+    fn test_build_vtimezone_berlin() {
+        let timezone = build_vtimezone(Europe::Berlin).to_string();
+
+        assert!(timezone.contains("TZID:Europe/Berlin"));
+        assert!(timezone.contains(concat!(
+            "BEGIN:STANDARD\r\n",
+            "DTSTART:19701025T030000\r\n",
+            "TZOFFSETFROM:+0200\r\n",
+            "TZOFFSETTO:+0100\r\n",
+            "TZNAME:CET\r\n",
+            "RRULE:FREQ=YEARLY;BYMONTH=10;BYDAY=-1SU\r\n",
+        )));
+        assert!(timezone.contains(concat!(
+            "BEGIN:DAYLIGHT\r\n",
+            "DTSTART:19700329T020000\r\n",
+            "TZOFFSETFROM:+0100\r\n",
+            "TZOFFSETTO:+0200\r\n",
+            "TZNAME:CEST\r\n",
+            "RRULE:FREQ=YEARLY;BYMONTH=3;BYDAY=-1SU\r\n",
+        )));
+    }
+
+    #[test]
+    fn test_build_vtimezone_nth_weekday() {
+        let timezone = build_vtimezone(America::New_York).to_string();
+
+        assert!(timezone.contains("DTSTART:19700308T020000"));
+        assert!(timezone.contains("RRULE:FREQ=YEARLY;BYMONTH=3;BYDAY=2SU"));
+        assert!(timezone.contains("DTSTART:19701101T020000"));
+        assert!(timezone.contains("RRULE:FREQ=YEARLY;BYMONTH=11;BYDAY=1SU"));
+    }
+
+    #[test]
+    fn test_build_vtimezone_fixed_offset() {
+        let timezone = build_vtimezone(Asia::Tokyo).to_string();
+
+        assert!(timezone.contains("TZOFFSETTO:+0900"));
+        assert!(!timezone.contains("DAYLIGHT"));
+        assert!(!timezone.contains("RRULE"));
+    }
+}
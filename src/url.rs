@@ -0,0 +1,267 @@
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{Datelike, Duration, NaiveDate};
+use http::Uri;
+use serde::Deserialize;
+
+#[derive(Debug)]
+pub enum UrlError {
+    InvalidUri,
+    MissingQuery,
+    MissingKey,
+    MalformedQuery(serde_urlencoded::de::Error),
+}
+
+impl fmt::Display for UrlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidUri => write!(f, "not a valid URL"),
+            Self::MissingQuery => write!(f, "URL has no query string"),
+            Self::MissingKey => write!(f, "URL has neither a key nor a user and file parameter"),
+            Self::MalformedQuery(err) => write!(f, "malformed query string: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for UrlError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::MalformedQuery(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+/// How a link identifies the calendar.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CalendarId {
+    /// The old (`key` only) or the new (`key` and `salt`) format.
+    Key { key: String, salt: Option<String> },
+    /// A calendar saved by a Rapla user.
+    User { user: String, file: String },
+}
+
+/// A Rapla calendar link, see [`CalendarId`] for the formats it comes in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RaplaUrl {
+    /// Everything before the query string, e.g. `https://rapla.dhbw.de/rapla/calendar`.
+    pub base_url: String,
+    pub id: CalendarId,
+    /// Rapla page to show if it isn't part of the path, e.g. `calendar`.
+    pub page: Option<String>,
+    pub day: Option<u32>,
+    pub month: Option<u32>,
+    pub year: Option<i32>,
+    /// Label of the button that submitted the date form, Rapla itself ignores it.
+    pub goto: Option<String>,
+    pub pages: Option<u32>,
+}
+
+#[derive(Deserialize)]
+struct RaplaUrlQuery {
+    key: Option<String>,
+    salt: Option<String>,
+    user: Option<String>,
+    file: Option<String>,
+    page: Option<String>,
+    day: Option<u32>,
+    month: Option<u32>,
+    year: Option<i32>,
+    goto: Option<String>,
+    pages: Option<u32>,
+}
+
+impl RaplaUrl {
+    pub fn parse(raw: &str) -> Result<Self, UrlError> {
+        Self::from_uri(&Uri::from_str(raw.trim()).map_err(|_| UrlError::InvalidUri)?)
+    }
+
+    pub fn from_uri(uri: &Uri) -> Result<Self, UrlError> {
+        let query: RaplaUrlQuery =
+            serde_urlencoded::from_str(uri.query().ok_or(UrlError::MissingQuery)?)
+                .map_err(UrlError::MalformedQuery)?;
+
+        let base_url = match (uri.scheme_str(), uri.authority()) {
+            (Some(scheme), Some(authority)) => format!("{scheme}://{authority}{}", uri.path()),
+            _ => uri.path().to_string(),
+        };
+
+        let id = match (query.key, query.user, query.file) {
+            (Some(key), _, _) => CalendarId::Key {
+                key,
+                salt: query.salt,
+            },
+            (None, Some(user), Some(file)) => CalendarId::User { user, file },
+            _ => return Err(UrlError::MissingKey),
+        };
+
+        Ok(Self {
+            base_url,
+            id,
+            page: query.page,
+            day: query.day,
+            month: query.month,
+            year: query.year,
+            goto: query.goto,
+            pages: query.pages,
+        })
+    }
+
+    /// Link to the week containing the given date, Rapla then starts the calendar on its monday.
+    pub fn for_week(base_url: impl Into<String>, key: impl Into<String>, date: NaiveDate) -> Self {
+        let monday = date - Duration::days(date.weekday().num_days_from_monday().into());
+        Self {
+            base_url: base_url.into(),
+            id: CalendarId::Key {
+                key: key.into(),
+                salt: None,
+            },
+            page: None,
+            day: Some(monday.day()),
+            month: Some(monday.month()),
+            year: Some(monday.year()),
+            goto: None,
+            pages: None,
+        }
+    }
+
+    #[must_use]
+    pub fn to_url(&self) -> String {
+        let mut query = Vec::new();
+        if let Some(page) = &self.page {
+            query.push(("page", page.clone()));
+        }
+        match &self.id {
+            CalendarId::Key { key, salt } => {
+                query.push(("key", key.clone()));
+                if let Some(salt) = salt {
+                    query.push(("salt", salt.clone()));
+                }
+            }
+            CalendarId::User { user, file } => {
+                query.push(("user", user.clone()));
+                query.push(("file", file.clone()));
+            }
+        }
+        for (name, value) in [("day", self.day), ("month", self.month)] {
+            if let Some(value) = value {
+                query.push((name, value.to_string()));
+            }
+        }
+        if let Some(year) = self.year {
+            query.push(("year", year.to_string()));
+        }
+        if let Some(goto) = &self.goto {
+            query.push(("goto", goto.clone()));
+        }
+        if let Some(pages) = self.pages {
+            query.push(("pages", pages.to_string()));
+        }
+
+        let query = serde_urlencoded::to_string(query).expect("string pairs should always encode");
+        format!("{}?{query}", self.base_url)
+    }
+}
+
+impl fmt::Display for RaplaUrl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_url())
+    }
+}
+
+impl FromStr for RaplaUrl {
+    type Err = UrlError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::{CalendarId, RaplaUrl};
+
+    #[test]
+    fn test_parse_old_format() {
+        let url = RaplaUrl::parse("https://rapla.dhbw.de/rapla?key=abc").unwrap();
+        assert_eq!(
+            url.id,
+            CalendarId::Key {
+                key: "abc".to_string(),
+                salt: None
+            }
+        );
+        assert_eq!(url.pages, None);
+    }
+
+    #[test]
+    fn test_parse_new_format() {
+        let url = RaplaUrl::parse(
+            "https://rapla.dhbw.de/rapla/calendar?key=6Q0QSbNt&salt=648736798&day=23&month=10&year=2023&goto=Datum+anzeigen&pages=1",
+        )
+        .unwrap();
+        assert_eq!(
+            url.id,
+            CalendarId::Key {
+                key: "6Q0QSbNt".to_string(),
+                salt: Some("648736798".to_string())
+            }
+        );
+        assert_eq!(
+            (url.day, url.month, url.year),
+            (Some(23), Some(10), Some(2023))
+        );
+        assert_eq!(url.goto.as_deref(), Some("Datum anzeigen"));
+        assert_eq!(url.pages, Some(1));
+    }
+
+    #[test]
+    fn test_parse_user_format() {
+        let url =
+            RaplaUrl::parse("https://rapla.dhbw.de/rapla?page=calendar&user=mueller&file=TINF22B")
+                .unwrap();
+        assert_eq!(url.base_url, "https://rapla.dhbw.de/rapla");
+        assert_eq!(url.page.as_deref(), Some("calendar"));
+        assert_eq!(
+            url.id,
+            CalendarId::User {
+                user: "mueller".to_string(),
+                file: "TINF22B".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_missing_key() {
+        assert!(RaplaUrl::parse("https://rapla.dhbw.de/rapla?salt=abc").is_err());
+        assert!(RaplaUrl::parse("https://rapla.dhbw.de/rapla?user=mueller").is_err());
+    }
+
+    #[test]
+    fn test_to_url_round_trip() {
+        let raw = "https://rapla.dhbw.de/rapla/calendar?key=6Q0QSbNt&salt=648736798&day=23&month=10&year=2023&pages=2";
+        let url = RaplaUrl::parse(raw).unwrap();
+        assert_eq!(url.base_url, "https://rapla.dhbw.de/rapla/calendar");
+        assert_eq!(url.to_url(), raw);
+
+        for raw in [
+            "https://rapla.dhbw.de/rapla?page=calendar&user=mueller&file=TINF22B&day=23&month=10&year=2023",
+            "https://rapla.dhbw.de/rapla/calendar?key=6Q0QSbNt&salt=648736798&day=23&month=10&year=2023&goto=Datum+anzeigen&pages=1",
+        ] {
+            assert_eq!(RaplaUrl::parse(raw).unwrap().to_url(), raw);
+        }
+    }
+
+    #[test]
+    fn test_for_week() {
+        let date = NaiveDate::from_ymd_opt(2023, 11, 1).unwrap();
+        let url = RaplaUrl::for_week("https://rapla.dhbw.de/rapla/calendar", "abc", date);
+        assert_eq!(
+            url.to_url(),
+            "https://rapla.dhbw.de/rapla/calendar?key=abc&day=30&month=10&year=2023"
+        );
+    }
+}
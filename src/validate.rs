@@ -0,0 +1,149 @@
+use std::collections::HashSet;
+use std::fmt;
+
+use chrono::{Datelike, Duration, NaiveTime, Weekday};
+
+use crate::calendar::{Calendar, Event};
+
+/// Something odd about an event, either a parser bug or an anomaly in the data itself.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationIssue {
+    ZeroDurationEvent {
+        event: Event,
+    },
+    EndBeforeStart {
+        event: Event,
+    },
+    /// Starts before 06:00 or ends after 23:00.
+    OutsideHours {
+        event: Event,
+    },
+    WeekendEvent {
+        event: Event,
+    },
+    EmptyTitle {
+        event: Event,
+    },
+    /// Identical to an event earlier in the calendar.
+    Duplicate {
+        event: Event,
+    },
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ZeroDurationEvent { event } => write!(f, "event has no duration: {event}"),
+            Self::EndBeforeStart { event } => write!(f, "event ends before it starts: {event}"),
+            Self::OutsideHours { event } => write!(f, "event is outside of usual hours: {event}"),
+            Self::WeekendEvent { event } => write!(f, "event is on a weekend: {event}"),
+            Self::EmptyTitle { event } => write!(f, "event has an empty title: {event}"),
+            Self::Duplicate { event } => write!(f, "event is listed twice: {event}"),
+        }
+    }
+}
+
+impl Calendar {
+    /// Issues in the order of the events they concern.
+    #[must_use]
+    pub fn validate(&self) -> Vec<ValidationIssue> {
+        let earliest = NaiveTime::from_hms_opt(6, 0, 0).expect("time should be valid");
+        let latest = NaiveTime::from_hms_opt(23, 0, 0).expect("time should be valid");
+
+        let mut issues = Vec::new();
+        let mut seen = HashSet::new();
+        for event in &self.events {
+            let duration = event.duration();
+            if duration == Duration::zero() {
+                issues.push(ValidationIssue::ZeroDurationEvent {
+                    event: event.clone(),
+                });
+            } else if duration < Duration::zero() {
+                issues.push(ValidationIssue::EndBeforeStart {
+                    event: event.clone(),
+                });
+            }
+
+            if event.start < earliest || event.end > latest || event.end_date != event.date {
+                issues.push(ValidationIssue::OutsideHours {
+                    event: event.clone(),
+                });
+            }
+
+            if matches!(event.date.weekday(), Weekday::Sat | Weekday::Sun) {
+                issues.push(ValidationIssue::WeekendEvent {
+                    event: event.clone(),
+                });
+            }
+
+            if event.title.trim().is_empty() {
+                issues.push(ValidationIssue::EmptyTitle {
+                    event: event.clone(),
+                });
+            }
+
+            if !seen.insert(event) {
+                issues.push(ValidationIssue::Duplicate {
+                    event: event.clone(),
+                });
+            }
+        }
+
+        issues
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, NaiveTime};
+
+    use super::ValidationIssue;
+    use crate::builder::{CalendarBuilder, EventBuilder};
+
+    #[test]
+    fn test_validate() {
+        let event = |day, start, end, title| {
+            EventBuilder::new(
+                NaiveDate::from_ymd_opt(2023, 10, day).unwrap(),
+                NaiveTime::from_hms_opt(start, 0, 0).unwrap(),
+                NaiveTime::from_hms_opt(end, 0, 0).unwrap(),
+                title,
+            )
+            .build()
+        };
+
+        let calendar = CalendarBuilder::new("TINF22B")
+            .extend_events([
+                event(23, 8, 10, "A"),
+                event(23, 8, 10, "A"),
+                event(24, 9, 9, "B"),
+                event(25, 22, 1, "C"),
+                event(28, 10, 12, " "),
+            ])
+            .build()
+            .unwrap();
+
+        let issues = calendar
+            .validate()
+            .into_iter()
+            .map(|issue| match issue {
+                ValidationIssue::ZeroDurationEvent { .. } => "zero duration",
+                ValidationIssue::EndBeforeStart { .. } => "end before start",
+                ValidationIssue::OutsideHours { .. } => "outside hours",
+                ValidationIssue::WeekendEvent { .. } => "weekend",
+                ValidationIssue::EmptyTitle { .. } => "empty title",
+                ValidationIssue::Duplicate { .. } => "duplicate",
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            issues,
+            [
+                "duplicate",
+                "zero duration",
+                "outside hours",
+                "weekend",
+                "empty title"
+            ]
+        );
+    }
+}
@@ -0,0 +1,20 @@
+//! Bindings for JS, built with
+//! `cargo rustc --lib --release --target wasm32-unknown-unknown --no-default-features --features wasm --crate-type cdylib`
+//! and passed through `wasm-bindgen --target web`.
+
+use wasm_bindgen::prelude::*;
+
+use crate::calendar::Calendar;
+
+/// Parses a Rapla page, the start year is taken from the page itself.
+/// Returns the calendar serialized as JSON, see [`Calendar::to_json`].
+#[wasm_bindgen(js_name = parseCalendar)]
+pub fn parse_calendar(html: &str) -> Result<String, JsError> {
+    Ok(Calendar::try_from(html)?.to_json()?)
+}
+
+/// Converts a calendar as returned by [`parse_calendar`] to ICS.
+#[wasm_bindgen(js_name = calendarToIcs)]
+pub fn calendar_to_ics(json: &str) -> Result<String, JsError> {
+    Ok(Calendar::from_json(json)?.to_ics_string())
+}